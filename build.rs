@@ -31,5 +31,170 @@ fn main() {
         map.write_all(&buf).unwrap();
     });
 
+    write_idna_map(Path::new(&out_dir));
+    write_joining_map(Path::new(&out_dir));
+    write_script_map(Path::new(&out_dir));
+    write_confusable_map(Path::new(&out_dir));
+
     println!("cargo:rerun-if-changed=build.rs");
 }
+
+const IDNA_VALID: u8 = 0;
+const IDNA_MAPPED: u8 = 1;
+const IDNA_IGNORED: u8 = 2;
+const IDNA_DISALLOWED: u8 = 3;
+
+/// Writes the IDNA mapping of every char, taken from the UTS #46 mapping table shipped by
+/// `idna_adapter` (nontransitional processing), with the STD3 rules: the ascii chars other
+/// than lowercase letters, digits, `-` and `.` are disallowed.
+fn write_idna_map(out_dir: &Path) {
+    let adapter = idna_adapter::Adapter::new();
+
+    write_range_table(&out_dir.join("idna.bin"), |c| match c {
+        Some(c) => idna_status(&adapter, c),
+        None => (IDNA_DISALLOWED, String::new()),
+    });
+}
+
+const JOINING_LEFT: u8 = 1;
+const JOINING_RIGHT: u8 = 2;
+const JOINING_TRANSPARENT: u8 = 4;
+const VIRAMA: u8 = 8;
+
+/// Writes the flags used by the CheckJoiners rule of IDNA 2008 (RFC 5892, appendix A): the
+/// joining type of every char, left or dual joining, right or dual joining or transparent,
+/// and whether it is a virama.
+fn write_joining_map(out_dir: &Path) {
+    use idna_adapter::{LEFT_OR_DUAL_JOINING_MASK, RIGHT_OR_DUAL_JOINING_MASK};
+
+    let adapter = idna_adapter::Adapter::new();
+
+    write_range_table(&out_dir.join("joining.bin"), |c| {
+        let Some(c) = c else {
+            return (0, String::new());
+        };
+
+        let joining_type = adapter.joining_type(c);
+        let mut flags = 0;
+
+        if joining_type.to_mask().intersects(LEFT_OR_DUAL_JOINING_MASK) {
+            flags |= JOINING_LEFT;
+        }
+
+        if joining_type
+            .to_mask()
+            .intersects(RIGHT_OR_DUAL_JOINING_MASK)
+        {
+            flags |= JOINING_RIGHT;
+        }
+
+        if joining_type.is_transparent() {
+            flags |= JOINING_TRANSPARENT;
+        }
+
+        if adapter.is_virama(c) {
+            flags |= VIRAMA;
+        }
+
+        (flags, String::new())
+    });
+}
+
+/// Writes the script of every char, such as "Latn" or "Cyrl", with the status 1. The chars
+/// shared by scripts, such as digits and combining marks, have the status 0.
+fn write_script_map(out_dir: &Path) {
+    use unicode_script::{Script, UnicodeScript};
+
+    write_range_table(&out_dir.join("script.bin"), |c| {
+        match c.map(|c| c.script()) {
+            None | Some(Script::Common | Script::Inherited | Script::Unknown) => (0, String::new()),
+            Some(script) => (1, script.short_name().to_string()),
+        }
+    });
+}
+
+/// Writes the confusable prototype of every char following UTS #39, such as "a" for the
+/// Cyrillic "а", with the status 1. The other chars have the status 0.
+fn write_confusable_map(out_dir: &Path) {
+    write_range_table(&out_dir.join("confusable.bin"), |c| {
+        let Some(c) = c else {
+            return (0, String::new());
+        };
+
+        let prototype = unicode_security::skeleton(c.encode_utf8(&mut [0; 4])).collect::<String>();
+
+        if prototype.len() == c.len_utf8() && prototype.starts_with(c) {
+            (0, String::new())
+        } else {
+            (1, prototype)
+        }
+    });
+}
+
+/// Writes a status and a string for every code point, read by `crate::range_table`.
+///
+/// Consecutive code points sharing the same status and string are merged in a range. The
+/// file starts with the number of ranges (u32), followed by the ranges of 16 bytes: the
+/// first char (u32), the last char (u32), the offset (u32) and the length (u8) of the string
+/// in the strings following the ranges, the status (u8) and 2 bytes of padding. Numbers are
+/// little endian.
+fn write_range_table(path: &Path, f: impl Fn(Option<char>) -> (u8, String)) {
+    let mut ranges: Vec<(u32, u32, u32, u8, u8)> = Vec::new();
+    let mut strings = String::new();
+    let mut offsets = std::collections::HashMap::new();
+
+    for index in 0..=(char::MAX as u32) {
+        let (status, s) = f(char::from_u32(index));
+
+        let offset = *offsets.entry(s.clone()).or_insert_with(|| {
+            strings.push_str(&s);
+            u32::try_from(strings.len() - s.len()).expect("u32")
+        });
+        let len = s.len().try_into().expect("u8");
+
+        if let Some(last) = ranges.last_mut() {
+            if last.1 + 1 == index && (last.2, last.3, last.4) == (offset, len, status) {
+                last.1 = index;
+                continue;
+            }
+        }
+
+        ranges.push((index, index, offset, len, status));
+    }
+
+    let mut out = std::io::BufWriter::new(fs::File::create(path).unwrap());
+
+    out.write_all(&u32::try_from(ranges.len()).unwrap().to_le_bytes())
+        .unwrap();
+
+    for (first, last, offset, len, status) in ranges {
+        out.write_all(&first.to_le_bytes()).unwrap();
+        out.write_all(&last.to_le_bytes()).unwrap();
+        out.write_all(&offset.to_le_bytes()).unwrap();
+        out.write_all(&[len, status, 0, 0]).unwrap();
+    }
+
+    out.write_all(strings.as_bytes()).unwrap();
+}
+
+fn idna_status(adapter: &idna_adapter::Adapter, c: char) -> (u8, String) {
+    // the mapper replaces the disallowed chars by U+FFFD, which is disallowed itself.
+    if c == '\u{FFFD}' {
+        return (IDNA_DISALLOWED, String::new());
+    }
+
+    let mapped = adapter
+        .map_normalize(std::iter::once(c))
+        .collect::<String>();
+    let std3 = |m: char| !m.is_ascii() || matches!(m, 'a'..='z' | '0'..='9' | '-' | '.');
+
+    if mapped.contains('\u{FFFD}') || !mapped.chars().all(std3) {
+        (IDNA_DISALLOWED, String::new())
+    } else if mapped.is_empty() {
+        (IDNA_IGNORED, String::new())
+    } else if mapped.len() == c.len_utf8() && mapped.starts_with(c) {
+        (IDNA_VALID, String::new())
+    } else {
+        (IDNA_MAPPED, mapped)
+    }
+}
@@ -0,0 +1,209 @@
+//! Database integrations, enabled by the `rusqlite` and `sqlx` features.
+//!
+//! Values are validated through their [Format](crate::form_str::Format) when read, a
+//! [FormatErr](crate::form_str::FormatErr) is surfaced as a decode error. An
+//! `Option<FormStr<F>>` is read as `None` from `NULL`, and [NoneIfEmpty](crate::NoneIfEmpty)
+//! can turn an empty string into `None` afterward.
+
+#[cfg(feature = "rusqlite")]
+mod rusqlite_impl {
+    use crate::{
+        form_str::{FormStr, Format, Storage},
+        str_ci::StringCi,
+    };
+    use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+    impl<F: Default + Format, S: Storage> FromSql for FormStr<F, S> {
+        fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+            FormStr::new(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e)))
+        }
+    }
+
+    impl<F, S: Storage> ToSql for FormStr<F, S> {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            Ok(ToSqlOutput::from(self.as_str()))
+        }
+    }
+
+    impl FromSql for StringCi {
+        fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+            String::column_result(value).map(StringCi)
+        }
+    }
+
+    impl ToSql for StringCi {
+        fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+            Ok(ToSqlOutput::from(self.0.as_str()))
+        }
+    }
+
+    #[test]
+    fn test_rusqlite() {
+        use crate::{
+            form_str::formats::{Lower, MaxLen, Trim},
+            NoneIfEmpty,
+        };
+        use rusqlite::Connection;
+
+        type Name = FormStr<Lower<MaxLen<5, Trim<()>>>>;
+
+        let conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch("CREATE TABLE t (id INTEGER, name TEXT)")
+            .unwrap();
+
+        let name = Name::new(" Bob ").unwrap();
+
+        conn.execute("INSERT INTO t VALUES (1, ?1)", [&name])
+            .unwrap();
+        conn.execute("INSERT INTO t VALUES (2, 'TOO LONG')", [])
+            .unwrap();
+        conn.execute("INSERT INTO t VALUES (3, NULL)", []).unwrap();
+        conn.execute("INSERT INTO t VALUES (4, '')", []).unwrap();
+
+        let get = |id: i32| {
+            conn.query_row("SELECT name FROM t WHERE id = ?1", [id], |r| {
+                r.get::<_, Option<Name>>(0)
+            })
+        };
+
+        assert_eq!(get(1).unwrap(), Some(name));
+        assert!(matches!(
+            get(2),
+            Err(rusqlite::Error::FromSqlConversionFailure(..))
+        ));
+        assert_eq!(get(3).unwrap(), None);
+        assert_eq!(get(4).unwrap().none_if_empty(), None);
+
+        let ci: StringCi = conn
+            .query_row("SELECT ?1", [&StringCi("Bob".into())], |r| r.get(0))
+            .unwrap();
+
+        assert_eq!(ci, StringCi("BOB".into()));
+    }
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx_impl {
+    use crate::{
+        form_str::{FormStr, Format, Storage},
+        str_ci::StringCi,
+    };
+    use sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type};
+
+    impl<'r, DB, F, S> Decode<'r, DB> for FormStr<F, S>
+    where
+        DB: Database,
+        &'r str: Decode<'r, DB>,
+        F: Default + Format,
+        S: Storage,
+    {
+        fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            let s = <&str as Decode<DB>>::decode(value)?;
+            Ok(FormStr::new(s)?)
+        }
+    }
+
+    impl<'q, DB, F, S> Encode<'q, DB> for FormStr<F, S>
+    where
+        DB: Database,
+        String: Encode<'q, DB>,
+        S: Storage,
+    {
+        fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            self.as_str().to_owned().encode(buf)
+        }
+    }
+
+    impl<DB: Database, F, S> Type<DB> for FormStr<F, S>
+    where
+        str: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <str as Type<DB>>::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <str as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'r, DB> Decode<'r, DB> for StringCi
+    where
+        DB: Database,
+        String: Decode<'r, DB>,
+    {
+        fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            String::decode(value).map(StringCi)
+        }
+    }
+
+    impl<'q, DB> Encode<'q, DB> for StringCi
+    where
+        DB: Database,
+        String: Encode<'q, DB>,
+    {
+        fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            self.0.encode_by_ref(buf)
+        }
+    }
+
+    impl<DB: Database> Type<DB> for StringCi
+    where
+        str: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <str as Type<DB>>::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <str as Type<DB>>::compatible(ty)
+        }
+    }
+
+    #[test]
+    fn test_sqlx() {
+        use crate::form_str::formats::{Lower, MaxLen, Trim};
+        use sqlx::{Connection, SqliteConnection};
+
+        type Name = FormStr<Lower<MaxLen<5, Trim<()>>>>;
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        rt.block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            let name = Name::new(" Bob ").unwrap();
+
+            let read: Option<Name> = sqlx::query_scalar("SELECT ?1")
+                .bind(&name)
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+
+            assert_eq!(read, Some(name));
+
+            let read = sqlx::query_scalar::<_, Name>("SELECT 'TOO LONG'")
+                .fetch_one(&mut conn)
+                .await;
+
+            assert!(matches!(read, Err(sqlx::Error::ColumnDecode { .. })));
+
+            let read: Option<Name> = sqlx::query_scalar("SELECT NULL")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+
+            assert_eq!(read, None);
+
+            let ci: StringCi = sqlx::query_scalar("SELECT ?1")
+                .bind(StringCi("Bob".into()))
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+
+            assert_eq!(ci, StringCi("BOB".into()));
+        });
+    }
+}
@@ -1,28 +1,70 @@
-use crate::fs::{validate_filename, FsError};
+use crate::{
+    cmp::{EqExt, OrdExt},
+    fs::{self, FilenamePolicy, FsError, Policy},
+    str_ci::StrCi,
+    url::{self, UrlError},
+    InlineStr,
+};
 use std::{
-    borrow::Cow,
+    borrow::{Borrow, Cow},
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter},
     hash::{Hash, Hasher},
     ops::Deref,
+    rc::Rc,
+    str::FromStr,
+    sync::Arc,
 };
 
 pub type Result<T> = std::result::Result<T, FormatErr>;
 
-/// A `Box<str>` that ensure a format.
+/// A [FormStr] which can be built from a literal at compile time, see [form_str!](crate::form_str!).
+pub type StaticFormStr<F> = FormStr<F, Cow<'static, str>>;
+
+/// Creates a [StaticFormStr] from a literal validated at compile time.
+///
+/// The literal must already be formatted: it must be left unchanged by the format.
+///
+/// # Example
+/// ```
+/// use str_utils::{form_str, form_str::{formats::{MaxLen, Trim}, StaticFormStr}};
+///
+/// type Name = MaxLen<10, Trim<()>>;
+///
+/// const NAME: StaticFormStr<Name> = form_str!(Name, "John");
+///
+/// assert_eq!(&*NAME, "John");
+/// ```
+///
+/// An invalid literal fails the compilation.
+///
+/// ```compile_fail
+/// use str_utils::{form_str, form_str::formats::MaxLen};
+///
+/// let name = form_str!(MaxLen<3, ()>, "John");
+/// ```
+#[macro_export]
+macro_rules! form_str {
+    ($f:ty, $s:expr) => {
+        const { $crate::form_str::StaticFormStr::<$f>::from_literal($s) }
+    };
+}
+
+/// A string that ensure a format.
 ///
-/// The generic parameter is a Format to enforce.
+/// The first generic parameter is a Format to enforce, the second one is the [Storage]
+/// holding the string, a `Box<str>` by default.
 #[derive(Clone)]
-pub struct FormStr<F>(Box<str>, F);
+pub struct FormStr<F, S = Box<str>>(S, F);
 
-impl<F> FormStr<F> {
+impl<F, S: Storage> FormStr<F, S> {
     #[inline]
     pub fn as_str(&self) -> &str {
         &self.0
     }
 }
 
-impl<F: Format> FormStr<F> {
+impl<F: Format, S: Storage> FormStr<F, S> {
     pub fn new(s: &str) -> Result<Self>
     where
         F: Default,
@@ -38,37 +80,94 @@ impl<F: Format> FormStr<F> {
     }
 
     pub fn with_format(format: F, s: &str) -> Result<Self> {
-        Ok(Self(
-            format
-                .format(Cow::Borrowed(s))?
-                .into_owned()
-                .into_boxed_str(),
-            format,
-        ))
+        Self::from_cow(format, Cow::Borrowed(s))
+    }
+
+    fn from_cow(format: F, s: Cow<'_, str>) -> Result<Self> {
+        Ok(Self(S::from_cow(format.format(s)?), format))
+    }
+
+    /// Transform the string and format the result again.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::form_str::{formats::MaxLen, FormStr};
+    ///
+    /// let s = FormStr::<MaxLen<5, ()>>::new("abc").unwrap();
+    ///
+    /// assert_eq!(&*s.clone().map(|s| s + "de").unwrap(), "abcde");
+    /// assert!(s.map(|s| s + "def").is_err());
+    /// ```
+    pub fn map<M>(self, f: M) -> Result<Self>
+    where
+        M: FnOnce(String) -> String,
+    {
+        Self::from_cow(self.1, Cow::Owned(f(self.0.into_string())))
+    }
+
+    /// Modify the string in place and format the result again.
+    ///
+    /// On error, the value is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::form_str::{formats::{Lower, MaxLen}, FormStr};
+    ///
+    /// let mut s = FormStr::<Lower<MaxLen<5, ()>>>::new("abc").unwrap();
+    ///
+    /// s.update(|s| s.push('D')).unwrap();
+    /// assert_eq!(&*s, "abcd");
+    ///
+    /// assert!(s.update(|s| s.push_str("ef")).is_err());
+    /// assert_eq!(&*s, "abcd");
+    /// ```
+    pub fn update<M>(&mut self, f: M) -> Result<()>
+    where
+        M: FnOnce(&mut String),
+    {
+        let mut s = self.as_str().to_owned();
+        f(&mut s);
+        self.0 = S::from_cow(self.1.format(Cow::Owned(s))?);
+        Ok(())
+    }
+}
+
+impl<F: ConstFormat> FormStr<F, Cow<'static, str>> {
+    /// Creates a [FormStr] from a literal without allocating.
+    ///
+    /// The literal must already be formatted, since no transformation is applied.
+    /// When evaluated in a const context, an invalid literal fails the compilation,
+    /// see [form_str!](crate::form_str!).
+    ///
+    /// # Panics
+    /// Panics if the literal does not follow the [ConstFormat::RULES].
+    pub const fn from_literal(s: &'static str) -> Self {
+        assert!(F::RULES.check(s), "literal does not match the format");
+        Self(Cow::Borrowed(s), F::FORMAT)
     }
 }
 
-impl<F> AsRef<str> for FormStr<F> {
+impl<F, S: Storage> AsRef<str> for FormStr<F, S> {
     #[inline]
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
-impl<F> Debug for FormStr<F> {
+impl<F, S: Storage> Debug for FormStr<F, S> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Debug::fmt(&self.0, f)
+        Debug::fmt(self.as_str(), f)
     }
 }
 
-impl<F: Default + FormatDefault> Default for FormStr<F> {
+impl<F: Default + FormatDefault, S: Storage> Default for FormStr<F, S> {
     fn default() -> Self {
-        Self("".into(), F::default())
+        Self(S::from_cow(Cow::Borrowed("")), F::default())
     }
 }
 
-impl<F> Deref for FormStr<F> {
+impl<F, S: Storage> Deref for FormStr<F, S> {
     type Target = str;
 
     #[inline]
@@ -77,30 +176,30 @@ impl<F> Deref for FormStr<F> {
     }
 }
 
-impl<F> Display for FormStr<F> {
+impl<F, S: Storage> Display for FormStr<F, S> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(&self.0, f)
+        Display::fmt(self.as_str(), f)
     }
 }
 
-impl<F> Eq for FormStr<F> {}
+impl<F: Format, S: Storage> Eq for FormStr<F, S> {}
 
-impl<'a, F> From<&'a FormStr<F>> for Cow<'a, str> {
-    fn from(value: &'a FormStr<F>) -> Self {
+impl<'a, F, S: Storage> From<&'a FormStr<F, S>> for Cow<'a, str> {
+    fn from(value: &'a FormStr<F, S>) -> Self {
         Cow::Borrowed(&value.0)
     }
 }
 
-impl<F> From<FormStr<F>> for Cow<'_, str> {
-    fn from(value: FormStr<F>) -> Self {
+impl<F, S: Storage> From<FormStr<F, S>> for Cow<'_, str> {
+    fn from(value: FormStr<F, S>) -> Self {
         Cow::Owned(value.0.into_string())
     }
 }
 
 macro_rules! from {
     ($t:ty) => {
-        impl<'a, F: Default + Format> TryFrom<$t> for FormStr<F> {
+        impl<'a, F: Default + Format, S: Storage> TryFrom<$t> for FormStr<F, S> {
             type Error = FormatErr;
 
             #[inline]
@@ -109,75 +208,184 @@ macro_rules! from {
             }
         }
     };
+    // owned input given to the format, reusing its allocation.
+    ($t:ty, $cow:expr) => {
+        impl<'a, F: Default + Format, S: Storage> TryFrom<$t> for FormStr<F, S> {
+            type Error = FormatErr;
+
+            #[inline]
+            fn try_from(value: $t) -> Result<Self> {
+                FormStr::from_cow(F::default(), $cow(value))
+            }
+        }
+    };
 }
 
 from!(&Box<str>);
 from!(&Cow<'a, str>);
-from!(&std::rc::Rc<str>);
-from!(&std::sync::Arc<str>);
+from!(&Rc<str>);
+from!(&Arc<str>);
+from!(&String);
 from!(&str);
-from!(Box<str>);
-from!(Cow<'a, str>);
-from!(std::rc::Rc<str>);
-from!(std::sync::Arc<str>);
+from!(Box<str>, |s: Box<str>| Cow::Owned(s.into_string()));
+from!(Cow<'a, str>, |s| s);
+from!(Rc<str>);
+from!(Arc<str>);
+from!(String, Cow::Owned);
+
+impl<F: Default + Format, S: Storage> FromStr for FormStr<F, S> {
+    type Err = FormatErr;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
 
-impl<F> Hash for FormStr<F> {
+impl<F: Format, S: Storage> Hash for FormStr<F, S> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.hash(state);
+        match F::EQ_POLICY {
+            EqPolicy::Exact => self.as_str().hash(state),
+            EqPolicy::Ai => self.as_str().hash_ai(state),
+            EqPolicy::AiCi => self.as_str().hash_ai_ci(state),
+            EqPolicy::Ci => self.as_str().hash_ci(state),
+        }
     }
 }
 
-impl<F> Ord for FormStr<F> {
-    #[inline]
+impl<F: Format, S: Storage> Ord for FormStr<F, S> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.0.cmp(&other.0)
+        match F::EQ_POLICY {
+            EqPolicy::Exact => self.as_str().cmp(other.as_str()),
+            EqPolicy::Ai => self.as_str().cmp_ai(other.as_str()),
+            EqPolicy::AiCi => self.as_str().cmp_ai_ci(other.as_str()),
+            EqPolicy::Ci => self.as_str().cmp_ci(other.as_str()),
+        }
     }
 }
 
-impl<F> PartialEq for FormStr<F> {
-    #[inline]
+impl<F: Format, S: Storage> PartialEq for FormStr<F, S> {
     fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        match F::EQ_POLICY {
+            EqPolicy::Exact => self.as_str() == other.as_str(),
+            EqPolicy::Ai => self.as_str().eq_ai(other.as_str()),
+            EqPolicy::AiCi => self.as_str().eq_ai_ci(other.as_str()),
+            EqPolicy::Ci => self.as_str().eq_ci(other.as_str()),
+        }
+    }
+}
+
+impl<F, S: Storage> PartialEq<StrCi> for FormStr<F, S> {
+    #[inline]
+    fn eq(&self, other: &StrCi) -> bool {
+        other == self
     }
 }
 
-impl<F> PartialOrd for FormStr<F> {
+impl<F: Format, S: Storage> PartialOrd for FormStr<F, S> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
 macro_rules! to {
-    ($t:ty) => {
-        impl<F> From<FormStr<F>> for $t {
+    ($t:ty, $into:ident) => {
+        impl<F, S: Storage> From<FormStr<F, S>> for $t {
             #[inline]
-            fn from(f: FormStr<F>) -> $t {
-                f.0.into()
+            fn from(f: FormStr<F, S>) -> $t {
+                f.0.$into()
             }
         }
 
-        impl<F> From<&FormStr<F>> for $t {
-            fn from(f: &FormStr<F>) -> $t {
-                f.0.clone().into()
+        impl<F, S: Storage> From<&FormStr<F, S>> for $t {
+            fn from(f: &FormStr<F, S>) -> $t {
+                f.0.clone().$into()
             }
         }
     };
 }
 
-to!(std::sync::Arc<str>);
-to!(std::rc::Rc<str>);
-to!(Box<str>);
-to!(String);
+to!(Arc<str>, into_arc);
+to!(Rc<str>, into_rc);
+to!(Box<str>, into_boxed_str);
+to!(String, into_string);
 
 #[cfg(feature = "serde")]
-impl<'de, F: Default + Format> serde::Deserialize<'de> for FormStr<F> {
+impl<'de, F: Default + Format, S: Storage> serde::Deserialize<'de> for FormStr<F, S> {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = <Cow<str>>::deserialize(deserializer)?;
-        FormStr::new(&s).map_err(serde::de::Error::custom)
+        serde::de::DeserializeSeed::deserialize(FormStrSeed::new(F::default()), deserializer)
+    }
+}
+
+/// Deserialize a [FormStr] using a format value, such as one carrying a runtime
+/// configuration given to [FormStr::with_format].
+///
+/// # Example
+/// ```
+/// use serde::de::DeserializeSeed;
+/// use str_utils::form_str::{formats::Lower, FormStr, FormStrSeed};
+///
+/// let mut de = serde_json::Deserializer::from_str(r#""HELLO""#);
+/// let s: FormStr<Lower<()>> = FormStrSeed::new(Lower(())).deserialize(&mut de).unwrap();
+///
+/// assert_eq!(&*s, "hello");
+/// ```
+#[cfg(feature = "serde")]
+pub struct FormStrSeed<F, S = Box<str>>(F, std::marker::PhantomData<S>);
+
+#[cfg(feature = "serde")]
+impl<F, S> FormStrSeed<F, S> {
+    pub fn new(format: F) -> Self {
+        Self(format, std::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: Format, S: Storage> serde::de::DeserializeSeed<'de> for FormStrSeed<F, S> {
+    type Value = FormStr<F, S>;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, F: Format, S: Storage> serde::de::Visitor<'de> for FormStrSeed<F, S> {
+    type Value = FormStr<F, S>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    // the rejected value is not echoed in the error, it may be sensitive.
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+        FormStr::from_cow(self.0, Cow::Borrowed(v))
+            .map_err(|e| E::invalid_value(serde::de::Unexpected::Other("string"), &e))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> std::result::Result<Self::Value, E> {
+        // the string is moved into the format to reuse its allocation.
+        FormStr::from_cow(self.0, Cow::Owned(v))
+            .map_err(|e| E::invalid_value(serde::de::Unexpected::Other("string"), &e))
+    }
+}
+
+/// Reports the failure as what was expected, such as
+/// `invalid value: string, expected a string satisfying max len 3`.
+///
+/// The error does not contain the field path, which only the deserializer knows. Wrap the
+/// deserializer with a crate such as `serde_path_to_error` to get it.
+#[cfg(feature = "serde")]
+impl serde::de::Expected for FormatErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a string satisfying {self}")
     }
 }
 
@@ -188,210 +396,1768 @@ fn test_deserialize() {
         "hel\"lo",
         &*serde_json::from_str::<FormStr<()>>("\"hel\\\"lo\"").unwrap()
     );
+
+    let e = serde_json::from_str::<FormStr<formats::MaxLen<3, ()>>>(r#""abcd""#).unwrap_err();
+
+    assert_eq!(
+        e.to_string(),
+        r#"invalid value: string, expected a string satisfying max len 3 at line 1 column 6"#
+    );
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct User {
+        name: FormStr<formats::MaxLen<3, ()>>,
+    }
+
+    let de = &mut serde_json::Deserializer::from_str(r#"{ "name": "abcd" }"#);
+    let e = serde_path_to_error::deserialize::<_, User>(de).unwrap_err();
+
+    assert_eq!(e.path().to_string(), "name");
+    assert!(!e.to_string().contains("abcd"));
 }
 
 #[cfg(feature = "serde")]
-impl<F: Format> serde::Serialize for FormStr<F> {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+impl<F: Format, S: Storage> serde::Serialize for FormStr<F, S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> std::result::Result<Ser::Ok, Ser::Error>
     where
-        S: serde::Serializer,
+        Ser: serde::Serializer,
     {
-        self.0.serialize(serializer)
+        self.as_str().serialize(serializer)
     }
 }
 
-/// Define a format for use with [FormStr].
-pub trait Format {
-    fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>>;
-}
+/// The string storage of a [FormStr].
+///
+/// The conversion methods default to copying the string; a backend overrides the one
+/// matching its own type to avoid reallocating.
+pub trait Storage: Clone + Deref<Target = str> {
+    fn from_cow(s: Cow<'_, str>) -> Self;
 
-impl Format for () {
-    fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-        Ok(s)
+    fn into_arc(self) -> Arc<str> {
+        Arc::from(&*self)
     }
-}
 
-/// A marker trait that allow a [FormStr] to contains "".
-pub trait FormatDefault {}
+    fn into_boxed_str(self) -> Box<str> {
+        Box::from(&*self)
+    }
 
-impl FormatDefault for () {}
+    fn into_rc(self) -> Rc<str> {
+        Rc::from(&*self)
+    }
 
-/// Errors that can occur during formating.
-#[derive(Clone, Copy, PartialEq)]
-pub enum FormatErr {
-    Fs(FsError),
-    MaxLen(usize),
-    MinLen,
+    fn into_string(self) -> String {
+        String::from(&*self)
+    }
 }
 
-impl Debug for FormatErr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Fs(e) => Debug::fmt(e, f),
-            Self::MaxLen(l) => write!(f, "max len {l}"),
-            Self::MinLen => f.write_str("min len"),
-        }
+impl Storage for Arc<str> {
+    #[inline]
+    fn from_cow(s: Cow<'_, str>) -> Self {
+        Arc::from(s)
     }
-}
 
-impl Display for FormatErr {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Debug::fmt(self, f)
+    #[inline]
+    fn into_arc(self) -> Arc<str> {
+        self
     }
 }
 
-impl std::error::Error for FormatErr {}
-
-pub mod formats {
-    use super::*;
-    use crate::fs::format_sub_path;
+impl Storage for Box<str> {
+    #[inline]
+    fn from_cow(s: Cow<'_, str>) -> Self {
+        s.into_owned().into_boxed_str()
+    }
 
-    #[derive(Clone, Copy, Default)]
-    pub struct Filename;
+    #[inline]
+    fn into_arc(self) -> Arc<str> {
+        Arc::from(self)
+    }
 
-    impl Format for Filename {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            match s {
-                Cow::Borrowed(s) => match validate_filename(s) {
-                    Ok(v) => Ok(Cow::Borrowed(v)),
-                    Err(e) => Err(FormatErr::Fs(e)),
-                },
-                Cow::Owned(s) => match validate_filename(&s) {
-                    // try to avoid allocating a new string if the string is already trimmed.
-                    Ok(v) => Ok(if v.len() == s.len() {
-                        Cow::Owned(s)
-                    } else {
-                        Cow::Owned(v.to_string())
-                    }),
-                    Err(e) => Err(FormatErr::Fs(e)),
-                },
-            }
-        }
+    #[inline]
+    fn into_boxed_str(self) -> Box<str> {
+        self
     }
 
-    impl FormatDefault for Filename {}
+    #[inline]
+    fn into_rc(self) -> Rc<str> {
+        Rc::from(self)
+    }
 
-    /// Makes every chars lower.
-    #[derive(Clone, Copy, Default)]
-    pub struct Lower<F>(pub F);
+    #[inline]
+    fn into_string(self) -> String {
+        str::into_string(self)
+    }
+}
 
-    impl<F: Format> Format for Lower<F> {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            Ok(Cow::Owned(self.0.format(s)?.to_lowercase()))
-        }
+impl Storage for Cow<'static, str> {
+    #[inline]
+    fn from_cow(s: Cow<'_, str>) -> Self {
+        Cow::Owned(s.into_owned())
     }
 
-    impl<F: FormatDefault> FormatDefault for Lower<F> {}
+    #[inline]
+    fn into_string(self) -> String {
+        self.into_owned()
+    }
+}
 
-    /// Enforce a maximum length, returns an error on overflow.
-    #[derive(Clone, Copy, Default)]
-    pub struct MaxLen<const N: usize, F>(pub F);
+impl<const N: usize> Storage for InlineStr<N> {
+    #[inline]
+    fn from_cow(s: Cow<'_, str>) -> Self {
+        InlineStr::from(s)
+    }
 
-    impl<const N: usize, F: Format> Format for MaxLen<N, F> {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            let s = self.0.format(s)?;
+    fn into_boxed_str(self) -> Box<str> {
+        self.into_string().into_boxed_str()
+    }
 
-            if s.len() < N {
-                return Ok(s);
-            }
+    fn into_string(self) -> String {
+        InlineStr::into_string(self)
+    }
+}
 
-            let count = s.chars().take(N + 1).count();
+impl Storage for Rc<str> {
+    #[inline]
+    fn from_cow(s: Cow<'_, str>) -> Self {
+        Rc::from(s)
+    }
 
-            if count > N {
-                Err(FormatErr::MaxLen(N))
-            } else {
-                Ok(s)
-            }
-        }
+    #[inline]
+    fn into_rc(self) -> Rc<str> {
+        self
     }
+}
 
-    impl<const N: usize, F: FormatDefault> FormatDefault for MaxLen<N, F> {}
+/// Define a format for use with [FormStr].
+pub trait Format {
+    /// The policy used by the `Eq`, `Hash` and `Ord` implementations of [FormStr].
+    const EQ_POLICY: EqPolicy = EqPolicy::Exact;
 
-    /// Enforce a minimum length, returns an error if not matching.
-    #[derive(Clone, Copy, Default)]
-    pub struct MinLen<const N: usize, F>(pub F);
+    /// Describe the constraints enforced by the format, such as for schema generation.
+    fn describe(&self) -> Description {
+        Description::default()
+    }
 
-    impl<const N: usize, F: Format> Format for MinLen<N, F> {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            let s = self.0.format(s)?;
-            let count = s.chars().take(N).count();
+    fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>>;
 
-            if count >= N {
-                Ok(s)
-            } else {
-                Err(FormatErr::MinLen)
+    /// Format the string like [Format::format], but keep going after an error, pushing
+    /// every violation into `errors`. Returns the string formatted on a best effort basis.
+    fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+        match self.format(s.clone()) {
+            Ok(s) => s,
+            Err(e) => {
+                errors.push(e);
+                s
             }
         }
     }
 
-    /// Enforce a sub path, such as `sub_dir/text.txt`
-    #[derive(Clone, Copy, Default)]
-    pub struct SubPath;
+    /// Returns every violation of the format instead of the first one.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::{
+    ///     form_str::{formats::{Filename, MaxLen}, Format, FormatErr},
+    ///     fs::FsError,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     MaxLen::<3, Filename>::default().validate_all("a?b.txt"),
+    ///     [FormatErr::Fs(FsError::InvalidChar('?')), FormatErr::MaxLen(3)]
+    /// );
+    /// ```
+    fn validate_all(&self, s: &str) -> Vec<FormatErr> {
+        let mut errors = Vec::new();
+        self.format_all(Cow::Borrowed(s), &mut errors);
+        errors
+    }
+}
 
-    impl Format for SubPath {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            match format_sub_path(&s) {
-                Ok(s) => Ok(Cow::Owned(s)),
-                Err(e) => Err(FormatErr::Fs(e)),
-            }
-        }
+impl Format for () {
+    fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+        Ok(s)
     }
 
-    /// Trim whitespace chars.
-    #[derive(Clone, Copy, Default)]
-    pub struct Trim<F>(pub F);
+    fn format_all<'a>(&self, s: Cow<'a, str>, _: &mut Vec<FormatErr>) -> Cow<'a, str> {
+        s
+    }
+}
 
-    impl<F: Format> Format for Trim<F> {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            Ok(match self.0.format(s)? {
-                Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
-                Cow::Owned(s) => Cow::Owned(s.trim().to_owned()),
-            })
+/// The case of the chars of a formatted string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Case {
+    Lower,
+    Upper,
+}
+
+/// The constraints enforced by a [Format], returned by [Format::describe].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Description {
+    /// The case of every char.
+    pub case: Option<Case>,
+    /// Chars which are never accepted, in addition to control chars when
+    /// [Description::no_control_chars] is set.
+    pub forbidden_chars: &'static [char],
+    /// The string must be a valid filename, see [fs::validate_filename].
+    pub filename: bool,
+    /// The maximum lengths, at most one per unit. Every one of them must be satisfied.
+    pub max_len: Vec<Length>,
+    /// The minimum lengths, at most one per unit. Every one of them must be satisfied.
+    pub min_len: Vec<Length>,
+    /// Control chars are never accepted.
+    pub no_control_chars: bool,
+    /// A regular expression the string must match, using the ECMA 262 syntax.
+    pub pattern: Option<&'static str>,
+    /// Names which are reserved, compared case insensitively.
+    pub reserved_names: &'static [&'static str],
+    /// The string must be a path relative to a folder, see [fs::validate_sub_path].
+    pub sub_path: bool,
+    /// The string does not start or end with whitespace chars.
+    pub trimmed: bool,
+    /// The string must be an absolute URL, see [url::Url::parse].
+    pub url: bool,
+}
+
+impl Description {
+    /// Add a maximum length, keeping the most restrictive one of each unit. The lengths in
+    /// other units are kept since they cannot be compared.
+    pub fn with_max_len(mut self, len: Length) -> Self {
+        match self.max_len.iter_mut().find(|l| l.kind == len.kind) {
+            Some(l) => l.len = l.len.min(len.len),
+            None => self.max_len.push(len),
         }
+
+        self
     }
 
-    impl<F: FormatDefault> FormatDefault for Trim<F> {}
+    /// Add a minimum length, keeping the most restrictive one of each unit. The lengths in
+    /// other units are kept since they cannot be compared.
+    pub fn with_min_len(mut self, len: Length) -> Self {
+        match self.min_len.iter_mut().find(|l| l.kind == len.kind) {
+            Some(l) => l.len = l.len.max(len.len),
+            None => self.min_len.push(len),
+        }
 
-    /// Makes all chars upper.
-    #[derive(Clone, Copy, Default)]
-    pub struct Upper<F>(pub F);
+        self
+    }
+}
 
-    impl<F: Format> Format for Upper<F> {
-        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
-            Ok(Cow::Owned(self.0.format(s)?.to_uppercase()))
+#[cfg(feature = "schemars")]
+impl Description {
+    /// Converts the description into a JSON schema of a string.
+    ///
+    /// JSON schema measures lengths in chars, so a maximum length in bytes or UTF-16 units
+    /// and a minimum length in graphemes are loosened into chars, other lengths are omitted.
+    pub fn json_schema(&self) -> schemars::Schema {
+        let mut schema = schemars::json_schema!({ "type": "string" });
+
+        let max_len = self
+            .max_len
+            .iter()
+            .filter(|l| l.kind != LenKind::Graphemes)
+            .map(|l| l.len)
+            .min();
+
+        if let Some(len) = max_len {
+            schema.insert("maxLength".into(), len.into());
         }
-    }
 
-    impl<F: FormatDefault> FormatDefault for Upper<F> {}
+        let min_len = self
+            .min_len
+            .iter()
+            .filter(|l| matches!(l.kind, LenKind::Chars | LenKind::Graphemes))
+            .map(|l| l.len)
+            .max();
 
-    #[test]
-    fn test_combination() {
-        type C = Lower<MaxLen<3, Trim<()>>>;
+        if let Some(len) = min_len {
+            schema.insert("minLength".into(), len.into());
+        }
 
-        assert!(FormStr::<C>::new(" Hello").is_err());
-        assert_eq!(&*FormStr::<C>::new(" Hel").unwrap(), "hel");
+        if let Some(pattern) = self.pattern {
+            schema.insert("pattern".into(), pattern.into());
+        }
 
-        type D = Lower<MinLen<1, MaxLen<3, Trim<()>>>>;
+        if self.url {
+            schema.insert("format".into(), "uri".into());
+        }
 
-        assert!(FormStr::<D>::new(" ").is_err());
-        assert_eq!(&*FormStr::<D>::new(" ABC").unwrap(), "abc");
+        schema
     }
+}
 
-    #[test]
-    fn test_lower() {
-        type L = Lower<()>;
-
-        assert_eq!(&*FormStr::<L>::new("Hello").unwrap(), "hello");
+#[cfg(feature = "schemars")]
+impl<F: Default + Format, S: Storage> schemars::JsonSchema for FormStr<F, S> {
+    fn inline_schema() -> bool {
+        true
     }
 
-    #[test]
-    fn test_upper() {
-        type U = Upper<()>;
+    fn schema_name() -> Cow<'static, str> {
+        "FormStr".into()
+    }
 
-        assert_eq!(&*FormStr::<U>::new("Hello").unwrap(), "HELLO");
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        F::default().describe().json_schema()
     }
 }
 
+#[cfg(all(feature = "schemars", test))]
+#[test]
+fn test_json_schema() {
+    use formats::{Filename, MaxLen, MinLen, Utf16};
+
+    let schema = schemars::schema_for!(FormStr<MinLen<1, MaxLen<50, Filename, Utf16>>>);
+
+    assert_eq!(schema.get("type").unwrap(), "string");
+    assert_eq!(schema.get("maxLength").unwrap(), 50);
+    assert_eq!(schema.get("minLength").unwrap(), 1);
+    assert!(schema.get("pattern").is_some());
+
+    let schema = schemars::schema_for!(FormStr<MaxLen<10, MaxLen<5, (), formats::Bytes>>>);
+
+    assert_eq!(schema.get("maxLength").unwrap(), 5);
+
+    let schema = schemars::schema_for!(FormStr<formats::Url>);
+
+    assert_eq!(schema.get("format").unwrap(), "uri");
+}
+
+/// A length measured in a unit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Length {
+    pub kind: LenKind,
+    pub len: usize,
+}
+
+/// Define how [FormStr] values are compared, hashed and ordered.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EqPolicy {
+    /// Exact comparison of the chars.
+    Exact,
+    /// Accent insensitive, see [EqExt::eq_ai].
+    Ai,
+    /// Accent / case insensitive, see [EqExt::eq_ai_ci].
+    AiCi,
+    /// Case insensitive, see [EqExt::eq_ci].
+    Ci,
+}
+
+/// A [Format] able to validate a literal at compile time, see [form_str!](crate::form_str!).
+pub trait ConstFormat: Format + Sized {
+    /// The format value stored in the [FormStr].
+    const FORMAT: Self;
+
+    /// The rules a literal must follow to be left unchanged by the format.
+    const RULES: LiteralRules;
+}
+
+impl ConstFormat for () {
+    const FORMAT: Self = ();
+    const RULES: LiteralRules = LiteralRules::NONE;
+}
+
+/// The rules a literal must follow to be accepted by a [ConstFormat].
+#[derive(Clone, Copy, Debug)]
+pub struct LiteralRules {
+    filename: bool,
+    lower: bool,
+    max_len: [usize; 4],
+    min_len: [usize; 4],
+    trim: bool,
+    upper: bool,
+}
+
+impl LiteralRules {
+    /// Accept every literal.
+    pub const NONE: Self = Self {
+        filename: false,
+        lower: false,
+        max_len: [usize::MAX; 4],
+        min_len: [0; 4],
+        trim: false,
+        upper: false,
+    };
+
+    /// Returns true if the literal follow the rules.
+    pub const fn check(&self, s: &str) -> bool {
+        let bytes = s.as_bytes();
+        let mut lens = [0; 4];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let (c, len) = decode_char(bytes, i);
+
+            if (self.filename && fs::is_invalid_filename_char(c))
+                || (self.lower && (c.is_uppercase() || is_titlecase(c)))
+                || (self.upper && (c.is_lowercase() || is_titlecase(c)))
+                || ((self.trim || self.filename)
+                    && c.is_whitespace()
+                    && (i == 0 || i + len == bytes.len()))
+            {
+                return false;
+            }
+
+            lens[LenKind::Bytes as usize] += len;
+            lens[LenKind::Chars as usize] += 1;
+            lens[LenKind::Utf16 as usize] += c.len_utf16();
+            i += len;
+        }
+
+        if self.filename
+            && ((!bytes.is_empty() && bytes[bytes.len() - 1] == b'.')
+                || fs::reserved_name(s).is_some())
+        {
+            return false;
+        }
+
+        let mut u = 0;
+
+        while u < lens.len() {
+            if lens[u] > self.max_len[u] || lens[u] < self.min_len[u] {
+                return false;
+            }
+
+            u += 1;
+        }
+
+        true
+    }
+
+    /// The literal must be a valid filename, see [fs::validate_filename], which is at most 255
+    /// UTF-16 units long.
+    pub const fn filename(mut self) -> Self {
+        self.filename = true;
+        self.max_len(LenKind::Utf16, 255)
+    }
+
+    /// The literal must not contains uppercase chars.
+    pub const fn lower(mut self) -> Self {
+        self.lower = true;
+        self
+    }
+
+    /// The literal must not be longer than `n` units.
+    ///
+    /// # Panics
+    /// Panics if the unit is [LenKind::Graphemes], which cannot be measured at compile time.
+    pub const fn max_len(mut self, unit: LenKind, n: usize) -> Self {
+        assert!(
+            !matches!(unit, LenKind::Graphemes),
+            "graphemes are not supported"
+        );
+
+        if n < self.max_len[unit as usize] {
+            self.max_len[unit as usize] = n;
+        }
+
+        self
+    }
+
+    /// The literal must not be shorter than `n` units.
+    ///
+    /// # Panics
+    /// Panics if the unit is [LenKind::Graphemes], which cannot be measured at compile time.
+    pub const fn min_len(mut self, unit: LenKind, n: usize) -> Self {
+        assert!(
+            !matches!(unit, LenKind::Graphemes),
+            "graphemes are not supported"
+        );
+
+        if n > self.min_len[unit as usize] {
+            self.min_len[unit as usize] = n;
+        }
+
+        self
+    }
+
+    /// The literal must not start or end with whitespace chars.
+    pub const fn trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// The literal must not contains lowercase chars.
+    pub const fn upper(mut self) -> Self {
+        self.upper = true;
+        self
+    }
+}
+
+/// The unit in which a length is measured, see [formats::LenUnit].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LenKind {
+    Bytes,
+    Chars,
+    Graphemes,
+    Utf16,
+}
+
+/// Returns true for the titlecase letters such as "ǅ", which are neither uppercase nor
+/// lowercase but change with both case mappings.
+const fn is_titlecase(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1C5}'
+            | '\u{1C8}'
+            | '\u{1CB}'
+            | '\u{1F2}'
+            | '\u{1F88}'..='\u{1F8F}'
+            | '\u{1F98}'..='\u{1F9F}'
+            | '\u{1FA8}'..='\u{1FAF}'
+            | '\u{1FBC}'
+            | '\u{1FCC}'
+            | '\u{1FFC}'
+    )
+}
+
+/// Decodes the char starting at byte `i` of a valid UTF-8 string, returns its byte length.
+const fn decode_char(b: &[u8], i: usize) -> (char, usize) {
+    let x = b[i] as u32;
+
+    let (u, len) = if x < 0x80 {
+        (x, 1)
+    } else if x < 0xE0 {
+        (((x & 0x1F) << 6) | (b[i + 1] as u32 & 0x3F), 2)
+    } else if x < 0xF0 {
+        (
+            ((x & 0x0F) << 12) | ((b[i + 1] as u32 & 0x3F) << 6) | (b[i + 2] as u32 & 0x3F),
+            3,
+        )
+    } else {
+        (
+            ((x & 0x07) << 18)
+                | ((b[i + 1] as u32 & 0x3F) << 12)
+                | ((b[i + 2] as u32 & 0x3F) << 6)
+                | (b[i + 3] as u32 & 0x3F),
+            4,
+        )
+    };
+
+    match char::from_u32(u) {
+        Some(c) => (c, len),
+        None => panic!("invalid utf-8"),
+    }
+}
+
+/// A marker trait that allow a [FormStr] to contains "".
+pub trait FormatDefault {}
+
+impl FormatDefault for () {}
+
+/// Errors that can occur during formating.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FormatErr {
+    Fs(FsError),
+    MaxLen(usize),
+    MinLen,
+    Url(UrlError),
+}
+
+impl Debug for FormatErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fs(e) => Debug::fmt(e, f),
+            Self::MaxLen(l) => write!(f, "max len {l}"),
+            Self::MinLen => f.write_str("min len"),
+            Self::Url(e) => Debug::fmt(e, f),
+        }
+    }
+}
+
+impl Display for FormatErr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for FormatErr {}
+
+pub mod formats {
+    use super::*;
+    use crate::{fs::format_sub_path, StrUtilsExt, TrimInPlace};
+    use unicode_segmentation::UnicodeSegmentation;
+
+    /// Compare, hash and order the [FormStr] accent insensitively.
+    #[derive(Clone, Copy, Default)]
+    pub struct Ai<F>(pub F);
+
+    impl<F: Format> Format for Ai<F> {
+        const EQ_POLICY: EqPolicy = EqPolicy::Ai;
+
+        #[inline]
+        fn describe(&self) -> Description {
+            self.0.describe()
+        }
+
+        #[inline]
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            self.0.format(s)
+        }
+
+        #[inline]
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            self.0.format_all(s, errors)
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for Ai<F> {}
+
+    impl<F: ConstFormat> ConstFormat for Ai<F> {
+        const FORMAT: Self = Ai(F::FORMAT);
+        const RULES: LiteralRules = F::RULES;
+    }
+
+    /// Compare, hash and order the [FormStr] accent / case insensitively.
+    #[derive(Clone, Copy, Default)]
+    pub struct AiCi<F>(pub F);
+
+    impl<F: Format> Format for AiCi<F> {
+        const EQ_POLICY: EqPolicy = EqPolicy::AiCi;
+
+        #[inline]
+        fn describe(&self) -> Description {
+            self.0.describe()
+        }
+
+        #[inline]
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            self.0.format(s)
+        }
+
+        #[inline]
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            self.0.format_all(s, errors)
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for AiCi<F> {}
+
+    impl<F: ConstFormat> ConstFormat for AiCi<F> {
+        const FORMAT: Self = AiCi(F::FORMAT);
+        const RULES: LiteralRules = F::RULES;
+    }
+
+    /// Compare, hash and order the [FormStr] case insensitively.
+    ///
+    /// When it is the outermost format, the [FormStr] can be borrowed as a [StrCi],
+    /// allowing `HashMap` lookups by [StrCi].
+    #[derive(Clone, Copy, Default)]
+    pub struct Ci<F>(pub F);
+
+    impl<F: Format> Format for Ci<F> {
+        const EQ_POLICY: EqPolicy = EqPolicy::Ci;
+
+        #[inline]
+        fn describe(&self) -> Description {
+            self.0.describe()
+        }
+
+        #[inline]
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            self.0.format(s)
+        }
+
+        #[inline]
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            self.0.format_all(s, errors)
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for Ci<F> {}
+
+    impl<F: ConstFormat> ConstFormat for Ci<F> {
+        const FORMAT: Self = Ci(F::FORMAT);
+        const RULES: LiteralRules = F::RULES;
+    }
+
+    impl<F: Format, S: Storage> Borrow<StrCi> for FormStr<Ci<F>, S> {
+        #[inline]
+        fn borrow(&self) -> &StrCi {
+            StrCi::new(&self.0)
+        }
+    }
+
+    #[derive(Clone, Copy, Default)]
+    pub struct Filename;
+
+    impl Format for Filename {
+        fn describe(&self) -> Description {
+            FilenamePolicy::Windows.describe()
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            format_filename(s, FilenamePolicy::Windows)
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            errors.extend(fs::validate_filename_all(&s).into_iter().map(FormatErr::Fs));
+            trim(s)
+        }
+    }
+
+    impl FormatDefault for Filename {}
+
+    impl ConstFormat for Filename {
+        const FORMAT: Self = Filename;
+        const RULES: LiteralRules = LiteralRules::NONE.filename();
+    }
+
+    /// Enforce a filename following the rules of a [FilenamePolicy], selected by one of the
+    /// [policies](fs::policies).
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::{form_str::{formats::FilenameFor, FormStr}, fs::policies::Posix};
+    ///
+    /// assert!(FormStr::<FilenameFor<Posix>>::try_from("a:b").is_ok());
+    /// assert!(FormStr::<FilenameFor<Posix>>::try_from("a/b").is_err());
+    /// ```
+    #[derive(Clone, Copy, Default)]
+    pub struct FilenameFor<P>(pub P);
+
+    impl<P: Policy> Format for FilenameFor<P> {
+        fn describe(&self) -> Description {
+            P::POLICY.describe()
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            format_filename(s, P::POLICY)
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            errors.extend(P::POLICY.validate_all(&s).into_iter().map(FormatErr::Fs));
+            trim(s)
+        }
+    }
+
+    impl<P: Policy> FormatDefault for FilenameFor<P> {}
+
+    fn format_filename(s: Cow<'_, str>, policy: FilenamePolicy) -> Result<Cow<'_, str>> {
+        match s {
+            Cow::Borrowed(s) => match policy.validate(s) {
+                Ok(v) => Ok(Cow::Borrowed(v)),
+                Err(e) => Err(FormatErr::Fs(e)),
+            },
+            Cow::Owned(s) => match policy.validate(&s) {
+                // try to avoid allocating a new string if the string is already trimmed.
+                Ok(v) => Ok(if v.len() == s.len() {
+                    Cow::Owned(s)
+                } else {
+                    Cow::Owned(v.to_string())
+                }),
+                Err(e) => Err(FormatErr::Fs(e)),
+            },
+        }
+    }
+
+    /// Makes every chars lower.
+    #[derive(Clone, Copy, Default)]
+    pub struct Lower<F>(pub F);
+
+    impl<F: Format> Format for Lower<F> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            Description {
+                case: Some(Case::Lower),
+                ..self.0.describe()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(Cow::Owned(self.0.format(s)?.to_lowercase()))
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            Cow::Owned(self.0.format_all(s, errors).to_lowercase())
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for Lower<F> {}
+
+    impl<F: ConstFormat> ConstFormat for Lower<F> {
+        const FORMAT: Self = Lower(F::FORMAT);
+        const RULES: LiteralRules = F::RULES.lower();
+    }
+
+    /// A unit used to measure the length of a string.
+    pub trait LenUnit {
+        const KIND: LenKind;
+
+        /// Counts the units of `s`, stopping once `limit` is reached.
+        fn count(s: &str, limit: usize) -> usize;
+
+        /// Returns the longest prefix of `s` having at most `max` units.
+        fn truncate(s: &str, max: usize) -> &str;
+    }
+
+    /// Measure the length in bytes, without splitting a char when truncating.
+    #[derive(Clone, Copy, Default)]
+    pub struct Bytes;
+
+    impl LenUnit for Bytes {
+        const KIND: LenKind = LenKind::Bytes;
+
+        #[inline]
+        fn count(s: &str, limit: usize) -> usize {
+            s.len().min(limit)
+        }
+
+        #[inline]
+        fn truncate(s: &str, max: usize) -> &str {
+            s.truncate_bytes(max)
+        }
+    }
+
+    /// Measure the length in chars.
+    #[derive(Clone, Copy, Default)]
+    pub struct Chars;
+
+    impl LenUnit for Chars {
+        const KIND: LenKind = LenKind::Chars;
+
+        #[inline]
+        fn count(s: &str, limit: usize) -> usize {
+            s.chars().take(limit).count()
+        }
+
+        #[inline]
+        fn truncate(s: &str, max: usize) -> &str {
+            s.truncate_chars(max)
+        }
+    }
+
+    /// Measure the length in extended grapheme clusters, as perceived by a user.
+    #[derive(Clone, Copy, Default)]
+    pub struct Graphemes;
+
+    impl LenUnit for Graphemes {
+        const KIND: LenKind = LenKind::Graphemes;
+
+        #[inline]
+        fn count(s: &str, limit: usize) -> usize {
+            s.graphemes(true).take(limit).count()
+        }
+
+        #[inline]
+        fn truncate(s: &str, max: usize) -> &str {
+            s.truncate_graphemes(max)
+        }
+    }
+
+    /// Measure the length in UTF-16 code units, such as a SQL Server `nvarchar` column.
+    #[derive(Clone, Copy, Default)]
+    pub struct Utf16;
+
+    impl LenUnit for Utf16 {
+        const KIND: LenKind = LenKind::Utf16;
+
+        fn count(s: &str, limit: usize) -> usize {
+            let mut units = 0;
+
+            for c in s.chars() {
+                if units >= limit {
+                    break;
+                }
+
+                units += c.len_utf16();
+            }
+
+            units.min(limit)
+        }
+
+        #[inline]
+        fn truncate(s: &str, max: usize) -> &str {
+            s.truncate_utf16(max)
+        }
+    }
+
+    /// A [LenUnit] which can be measured at compile time, see [ConstFormat].
+    pub trait ConstLenUnit: LenUnit + Sized {
+        const UNIT: Self;
+    }
+
+    impl ConstLenUnit for Bytes {
+        const UNIT: Self = Bytes;
+    }
+
+    impl ConstLenUnit for Chars {
+        const UNIT: Self = Chars;
+    }
+
+    impl ConstLenUnit for Utf16 {
+        const UNIT: Self = Utf16;
+    }
+
+    /// Enforce a maximum length, returns an error on overflow.
+    ///
+    /// The length is measured in chars unless another [LenUnit] is specified.
+    ///
+    /// Since 0.11, the unit is the second field: build it with [MaxLen::new] or
+    /// `MaxLen(format, Chars)` instead of `MaxLen(format)`.
+    #[derive(Clone, Copy, Default)]
+    pub struct MaxLen<const N: usize, F, U = Chars>(pub F, pub U);
+
+    impl<const N: usize, F, U: ConstLenUnit> MaxLen<N, F, U> {
+        #[inline]
+        pub const fn new(format: F) -> Self {
+            Self(format, U::UNIT)
+        }
+    }
+
+    impl<const N: usize, F: Format, U: LenUnit> Format for MaxLen<N, F, U> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            self.0.describe().with_max_len(Length {
+                kind: U::KIND,
+                len: N,
+            })
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            let s = self.0.format(s)?;
+
+            // no unit can be longer than the byte length.
+            if s.len() <= N {
+                return Ok(s);
+            }
+
+            if U::count(&s, N + 1) > N {
+                Err(FormatErr::MaxLen(N))
+            } else {
+                Ok(s)
+            }
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            let s = self.0.format_all(s, errors);
+
+            if s.len() > N && U::count(&s, N + 1) > N {
+                errors.push(FormatErr::MaxLen(N));
+            }
+
+            s
+        }
+    }
+
+    impl<const N: usize, F: FormatDefault, U> FormatDefault for MaxLen<N, F, U> {}
+
+    impl<const N: usize, F: ConstFormat, U: ConstLenUnit> ConstFormat for MaxLen<N, F, U> {
+        const FORMAT: Self = MaxLen::new(F::FORMAT);
+        const RULES: LiteralRules = F::RULES.max_len(U::KIND, N);
+    }
+
+    /// Enforce a minimum length, returns an error if not matching.
+    ///
+    /// The length is measured in chars unless another [LenUnit] is specified.
+    ///
+    /// Since 0.11, the unit is the second field: build it with [MinLen::new] or
+    /// `MinLen(format, Chars)` instead of `MinLen(format)`.
+    #[derive(Clone, Copy, Default)]
+    pub struct MinLen<const N: usize, F, U = Chars>(pub F, pub U);
+
+    impl<const N: usize, F, U: ConstLenUnit> MinLen<N, F, U> {
+        #[inline]
+        pub const fn new(format: F) -> Self {
+            Self(format, U::UNIT)
+        }
+    }
+
+    impl<const N: usize, F: Format, U: LenUnit> Format for MinLen<N, F, U> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            self.0.describe().with_min_len(Length {
+                kind: U::KIND,
+                len: N,
+            })
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            let s = self.0.format(s)?;
+
+            if U::count(&s, N) >= N {
+                Ok(s)
+            } else {
+                Err(FormatErr::MinLen)
+            }
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            let s = self.0.format_all(s, errors);
+
+            if U::count(&s, N) < N {
+                errors.push(FormatErr::MinLen);
+            }
+
+            s
+        }
+    }
+
+    impl<const N: usize, F: ConstFormat, U: ConstLenUnit> ConstFormat for MinLen<N, F, U> {
+        const FORMAT: Self = MinLen::new(F::FORMAT);
+        const RULES: LiteralRules = F::RULES.min_len(U::KIND, N);
+    }
+
+    /// Repair a filename with [sanitize_filename](fs::sanitize_filename) instead of rejecting it.
+    #[derive(Clone, Copy, Default)]
+    pub struct SanitizedFilename(pub fs::SanitizeOptions);
+
+    impl Format for SanitizedFilename {
+        fn describe(&self) -> Description {
+            Filename.describe().with_max_len(Length {
+                kind: LenKind::Bytes,
+                len: self.0.max_bytes.max(1),
+            })
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(match s {
+                Cow::Borrowed(s) => fs::sanitize_filename(s, self.0),
+                Cow::Owned(s) => match fs::sanitize_filename(&s, self.0) {
+                    Cow::Borrowed(_) => Cow::Owned(s),
+                    Cow::Owned(v) => Cow::Owned(v),
+                },
+            })
+        }
+    }
+
+    impl FormatDefault for SanitizedFilename {}
+
+    /// Enforce a slug such as "cafe-a-l-ete-2024", see [url::validate_slug_with]. Slugs are
+    /// built with [url::slugify].
+    #[derive(Clone, Copy, Default)]
+    pub struct Slug(pub url::SlugSeparator);
+
+    impl Format for Slug {
+        fn describe(&self) -> Description {
+            Description {
+                case: Some(Case::Lower),
+                pattern: Some(self.0.pattern()),
+                trimmed: true,
+                ..Description::default()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            match url::validate_slug_with(&s, self.0) {
+                Ok(()) => Ok(s),
+                Err(e) => Err(FormatErr::Url(e)),
+            }
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            if let Err(e) = url::validate_slug_with(&s, self.0) {
+                errors.push(FormatErr::Url(e));
+            }
+
+            s
+        }
+    }
+
+    /// Enforce a sub path, such as `sub_dir/text.txt`
+    #[derive(Clone, Copy, Default)]
+    pub struct SubPath;
+
+    impl Format for SubPath {
+        fn describe(&self) -> Description {
+            Description {
+                forbidden_chars: &fs::INVALID_SUB_PATH_CHARS,
+                no_control_chars: true,
+                reserved_names: &fs::RESERVED_NAMES,
+                sub_path: true,
+                trimmed: true,
+                ..Description::default()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            match format_sub_path(&s) {
+                Ok(s) => Ok(Cow::Owned(s)),
+                Err(e) => Err(FormatErr::Fs(e)),
+            }
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            match self.format(s.clone()) {
+                Ok(s) => s,
+                Err(_) => {
+                    errors.extend(fs::validate_sub_path_all(&s).into_iter().map(FormatErr::Fs));
+                    s
+                }
+            }
+        }
+    }
+
+    impl<S: Storage> AsRef<fs::SubPath> for FormStr<SubPath, S> {
+        #[inline]
+        fn as_ref(&self) -> &fs::SubPath {
+            // the format guarantees a sub path separated by `/`.
+            fs::SubPath::from_canonical(&self.0)
+        }
+    }
+
+    /// Trim whitespace chars.
+    #[derive(Clone, Copy, Default)]
+    pub struct Trim<F>(pub F);
+
+    impl<F: Format> Format for Trim<F> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            Description {
+                trimmed: true,
+                ..self.0.describe()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(trim(self.0.format(s)?))
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            trim(self.0.format_all(s, errors))
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for Trim<F> {}
+
+    fn trim(s: Cow<'_, str>) -> Cow<'_, str> {
+        match s {
+            Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+            Cow::Owned(mut s) => {
+                s.trim_in_place();
+                Cow::Owned(s)
+            }
+        }
+    }
+
+    impl<F: ConstFormat> ConstFormat for Trim<F> {
+        const FORMAT: Self = Trim(F::FORMAT);
+        const RULES: LiteralRules = F::RULES.trim();
+    }
+
+    /// Silently truncate the string to a maximum length.
+    ///
+    /// The length is measured in chars unless another [LenUnit] is specified.
+    #[derive(Clone, Copy, Default)]
+    pub struct Truncate<const N: usize, F, U = Chars>(pub F, pub U);
+
+    impl<const N: usize, F: Format, U: LenUnit> Format for Truncate<N, F, U> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            self.0.describe().with_max_len(Length {
+                kind: U::KIND,
+                len: N,
+            })
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(truncate::<U>(self.0.format(s)?, N, ""))
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            truncate::<U>(self.0.format_all(s, errors), N, "")
+        }
+    }
+
+    impl<const N: usize, F: FormatDefault, U> FormatDefault for Truncate<N, F, U> {}
+
+    impl<const N: usize, F: ConstFormat, U: ConstLenUnit> ConstFormat for Truncate<N, F, U> {
+        const FORMAT: Self = Truncate(F::FORMAT, U::UNIT);
+        const RULES: LiteralRules = F::RULES.max_len(U::KIND, N);
+    }
+
+    /// Silently truncate the string to a maximum length, ending it with `…` when truncated.
+    ///
+    /// The ellipsis is counted in the maximum length, which is measured in chars unless
+    /// another [LenUnit] is specified.
+    #[derive(Clone, Copy, Default)]
+    pub struct TruncateEllipsis<const N: usize, F, U = Chars>(pub F, pub U);
+
+    impl<const N: usize, F: Format, U: LenUnit> Format for TruncateEllipsis<N, F, U> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            self.0.describe().with_max_len(Length {
+                kind: U::KIND,
+                len: N,
+            })
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(truncate::<U>(self.0.format(s)?, N, "…"))
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            truncate::<U>(self.0.format_all(s, errors), N, "…")
+        }
+    }
+
+    impl<const N: usize, F: FormatDefault, U> FormatDefault for TruncateEllipsis<N, F, U> {}
+
+    impl<const N: usize, F: ConstFormat, U: ConstLenUnit> ConstFormat for TruncateEllipsis<N, F, U> {
+        const FORMAT: Self = TruncateEllipsis(F::FORMAT, U::UNIT);
+        const RULES: LiteralRules = F::RULES.max_len(U::KIND, N);
+    }
+
+    fn truncate<'a, U: LenUnit>(s: Cow<'a, str>, max: usize, ellipsis: &str) -> Cow<'a, str> {
+        if U::truncate(&s, max).len() == s.len() {
+            return s;
+        }
+
+        let ellipsis_len = U::count(ellipsis, max + 1);
+
+        // drop the ellipsis if it cannot fit.
+        let (len, ellipsis) = if ellipsis_len > max {
+            (U::truncate(&s, max).len(), "")
+        } else {
+            (U::truncate(&s, max - ellipsis_len).len(), ellipsis)
+        };
+
+        match s {
+            Cow::Borrowed(s) if ellipsis.is_empty() => Cow::Borrowed(&s[..len]),
+            s => {
+                let mut s = s.into_owned();
+                s.truncate(len);
+                s.push_str(ellipsis);
+                Cow::Owned(s)
+            }
+        }
+    }
+
+    /// Makes all chars upper.
+    #[derive(Clone, Copy, Default)]
+    pub struct Upper<F>(pub F);
+
+    impl<F: Format> Format for Upper<F> {
+        const EQ_POLICY: EqPolicy = F::EQ_POLICY;
+
+        fn describe(&self) -> Description {
+            Description {
+                case: Some(Case::Upper),
+                ..self.0.describe()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            Ok(Cow::Owned(self.0.format(s)?.to_uppercase()))
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            Cow::Owned(self.0.format_all(s, errors).to_uppercase())
+        }
+    }
+
+    impl<F: FormatDefault> FormatDefault for Upper<F> {}
+
+    impl<F: ConstFormat> ConstFormat for Upper<F> {
+        const FORMAT: Self = Upper(F::FORMAT);
+        const RULES: LiteralRules = F::RULES.upper();
+    }
+
+    /// Enforce an absolute URL, see [url::Url::parse]. The URL is trimmed.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::form_str::{formats::Url, FormStr};
+    ///
+    /// let url = FormStr::<Url>::new(" https://example.com/a?b ").unwrap();
+    ///
+    /// assert_eq!(&*url, "https://example.com/a?b");
+    /// assert!(FormStr::<Url>::new("https://example.com/%zz").is_err());
+    /// ```
+    #[derive(Clone, Copy, Default)]
+    pub struct Url;
+
+    impl Format for Url {
+        fn describe(&self) -> Description {
+            Description {
+                no_control_chars: true,
+                trimmed: true,
+                url: true,
+                ..Description::default()
+            }
+        }
+
+        fn format<'a>(&self, s: Cow<'a, str>) -> Result<Cow<'a, str>> {
+            let s = trim(s);
+
+            match url::Url::parse(&s) {
+                Ok(_) => Ok(s),
+                Err(e) => Err(FormatErr::Url(e)),
+            }
+        }
+
+        fn format_all<'a>(&self, s: Cow<'a, str>, errors: &mut Vec<FormatErr>) -> Cow<'a, str> {
+            let s = trim(s);
+
+            if let Err(e) = url::Url::parse(&s) {
+                errors.push(FormatErr::Url(e));
+            }
+
+            s
+        }
+    }
+
+    impl<S: Storage> FormStr<Url, S> {
+        /// The components of the URL.
+        pub fn url(&self) -> url::Url<'_> {
+            // the format guarantees a valid URL.
+            url::Url::parse(&self.0).expect("valid url")
+        }
+    }
+
+    #[test]
+    fn test_combination() {
+        type C = Lower<MaxLen<3, Trim<()>>>;
+
+        assert!(FormStr::<C>::new(" Hello").is_err());
+        assert_eq!(&*FormStr::<C>::new(" Hel").unwrap(), "hel");
+
+        type D = Lower<MinLen<1, MaxLen<3, Trim<()>>>>;
+
+        assert!(FormStr::<D>::new(" ").is_err());
+        assert_eq!(&*FormStr::<D>::new(" ABC").unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_url() {
+        let u = FormStr::<Url>::new(" http://a.b:80/c ").unwrap();
+
+        assert_eq!(&*u, "http://a.b:80/c");
+        assert_eq!(u.url().host(), Some("a.b"));
+        assert_eq!(u.url().port(), Some(80));
+        assert_eq!(
+            FormStr::<Url>::new("a.b/c"),
+            Err(FormatErr::Url(UrlError::MissingScheme))
+        );
+        assert_eq!(
+            MaxLen::<5, Url>::default().validate_all("http://a b"),
+            [
+                FormatErr::Url(UrlError::InvalidChar(' ')),
+                FormatErr::MaxLen(5)
+            ]
+        );
+        assert!(Url.describe().url);
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(&*FormStr::<Slug>::new("a-b-2").unwrap(), "a-b-2");
+        assert_eq!(
+            FormStr::<Slug>::new("a b"),
+            Err(FormatErr::Url(UrlError::InvalidChar(' ')))
+        );
+        assert_eq!(
+            FormStr::<Slug>::new(""),
+            Err(FormatErr::Url(UrlError::InvalidSlug))
+        );
+
+        let underscore = Slug(crate::url::SlugSeparator::Underscore);
+
+        assert!(underscore.validate_all("a_b_2").is_empty());
+        assert_eq!(
+            underscore.validate_all("a-b"),
+            [FormatErr::Url(UrlError::InvalidChar('-'))]
+        );
+        assert_eq!(
+            underscore.describe().pattern,
+            Some("^[a-z0-9]+(_[a-z0-9]+)*$")
+        );
+    }
+
+    #[test]
+    fn test_lower() {
+        type L = Lower<()>;
+
+        assert_eq!(&*FormStr::<L>::new("Hello").unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_validate_all() {
+        type T = Lower<MinLen<5, MaxLen<3, Trim<Filename>>>>;
+
+        assert_eq!(
+            T::default().validate_all(" Ab:c*d. "),
+            [
+                FormatErr::Fs(FsError::AlternateDataStream),
+                FormatErr::Fs(FsError::InvalidChar('*')),
+                FormatErr::Fs(FsError::EndsWithDot),
+                FormatErr::MaxLen(3),
+            ]
+        );
+
+        assert_eq!(T::default().validate_all("ab"), [FormatErr::MinLen]);
+        assert!(MaxLen::<3, Trim<()>>::default()
+            .validate_all(" abc ")
+            .is_empty());
+        assert_eq!(
+            MinLen::<2, (), Bytes>::new(()).validate_all("a"),
+            [FormatErr::MinLen]
+        );
+        assert_eq!(
+            SubPath.validate_all("/a/con"),
+            [
+                FormatErr::Fs(FsError::Root),
+                FormatErr::Fs(FsError::EmptySegment),
+                FormatErr::Fs(FsError::ReservedName("CON")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filename_for() {
+        use crate::fs::policies::{PortablePosix, Posix, Windows};
+
+        assert_eq!(
+            &*FormStr::<FilenameFor<Posix>>::new(" a:b ").unwrap(),
+            "a:b"
+        );
+        assert_eq!(
+            FormStr::<FilenameFor<Windows>>::new("ab:c"),
+            Err(FormatErr::Fs(FsError::AlternateDataStream))
+        );
+        assert_eq!(
+            FilenameFor(PortablePosix).validate_all("-a b"),
+            [
+                FormatErr::Fs(FsError::InvalidChar(' ')),
+                FormatErr::Fs(FsError::StartsWithHyphen)
+            ]
+        );
+        assert_eq!(
+            FilenameFor(Posix).describe().max_len,
+            [Length {
+                kind: LenKind::Bytes,
+                len: 255
+            }]
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let d = Lower::<MaxLen<5, MinLen<1, MaxLen<10, Trim<()>>>>>::default().describe();
+
+        assert_eq!(d.case, Some(Case::Lower));
+        assert!(d.trimmed);
+        assert_eq!(
+            d.max_len,
+            [Length {
+                kind: LenKind::Chars,
+                len: 5
+            }]
+        );
+        assert_eq!(
+            d.min_len,
+            [Length {
+                kind: LenKind::Chars,
+                len: 1
+            }]
+        );
+
+        let d = MaxLen::<10, Filename, Utf16>::default().describe();
+
+        assert!(d.filename);
+        assert!(d.forbidden_chars.contains(&'/'));
+        assert_eq!(
+            d.max_len,
+            [Length {
+                kind: LenKind::Utf16,
+                len: 10
+            }]
+        );
+
+        // a stricter length in another unit is not replaced.
+        let d = MaxLen::<10, MaxLen<5, (), Bytes>>::default().describe();
+
+        assert_eq!(
+            d.max_len,
+            [
+                Length {
+                    kind: LenKind::Bytes,
+                    len: 5
+                },
+                Length {
+                    kind: LenKind::Chars,
+                    len: 10
+                }
+            ]
+        );
+        assert!(!SubPath.describe().forbidden_chars.contains(&'/'));
+
+        let p = FormStr::<SubPath>::new(" a\\ b /c.txt").unwrap();
+
+        assert_eq!(&*p, "a/b/c.txt");
+        assert_eq!(AsRef::<fs::SubPath>::as_ref(&p).file_name(), "c.txt");
+        assert_eq!(().describe(), Description::default());
+    }
+
+    #[test]
+    fn test_eq_policy() {
+        use std::collections::HashMap;
+
+        type C = Ci<Trim<()>>;
+
+        assert_eq!(
+            FormStr::<C>::new("Bob").unwrap(),
+            FormStr::<C>::new("BOB").unwrap()
+        );
+        assert_ne!(
+            FormStr::<Trim<()>>::new("Bob"),
+            FormStr::<Trim<()>>::new("BOB")
+        );
+        assert_eq!(
+            FormStr::<Ai<()>>::new("Café"),
+            FormStr::<Ai<()>>::new("Cafe")
+        );
+        assert_ne!(
+            FormStr::<Ai<()>>::new("Café"),
+            FormStr::<Ai<()>>::new("CAFE")
+        );
+        assert_eq!(
+            FormStr::<AiCi<()>>::new("Café"),
+            FormStr::<AiCi<()>>::new("CAFE")
+        );
+        assert_eq!(
+            FormStr::<C>::new("a")
+                .unwrap()
+                .cmp(&FormStr::<C>::new("B").unwrap()),
+            Ordering::Less
+        );
+
+        let mut map = HashMap::new();
+        map.insert(FormStr::<C>::new(" Bob ").unwrap(), 1);
+
+        assert_eq!(map.get(StrCi::new("bob")), Some(&1));
+        assert!(FormStr::<C>::new("Bob").unwrap() == *StrCi::new("bOB"));
+    }
+
+    #[test]
+    fn test_from_owned() {
+        type T = Trim<()>;
+
+        let s = String::from("Hello");
+        let ptr = s.as_ptr();
+        let f = FormStr::<T>::try_from(s).unwrap();
+
+        // the allocation is reused when the format leaves the string unchanged.
+        assert_eq!(f.as_ptr(), ptr);
+        assert_eq!(&*FormStr::<T>::try_from(" a ".to_string()).unwrap(), "a");
+        assert_eq!(
+            &*FormStr::<T>::try_from(Box::<str>::from(" a ")).unwrap(),
+            "a"
+        );
+        assert_eq!(&*" A ".parse::<FormStr<Lower<T>>>().unwrap(), "a");
+        assert!("abc".parse::<FormStr<MaxLen<2, T>>>().is_err());
+    }
+
+    #[test]
+    fn test_sanitized_filename() {
+        type T = MinLen<1, SanitizedFilename>;
+
+        assert_eq!(&*FormStr::<T>::new(" a/b:c.txt ").unwrap(), "a_b_c.txt");
+        assert_eq!(&*FormStr::<T>::new("prn.pdf").unwrap(), "prn_.pdf");
+        assert!(FormStr::<T>::new("").is_err());
+    }
+
+    #[test]
+    fn test_storage() {
+        type T = Lower<Trim<()>>;
+
+        let a = FormStr::<T, Arc<str>>::new(" Hello ").unwrap();
+        let r = FormStr::<T, Rc<str>>::new(" Hello ").unwrap();
+        let i = FormStr::<T, InlineStr>::new(" Hello ").unwrap();
+
+        assert_eq!(&*a, "hello");
+        assert_eq!(&*r, "hello");
+        assert_eq!(&*i, "hello");
+        assert_eq!(a, a.clone());
+        assert!(Arc::ptr_eq(
+            &Arc::<str>::from(&a),
+            &Arc::<str>::from(a.clone())
+        ));
+        assert_eq!(String::from(i), "hello");
+        assert_eq!(&*Box::<str>::from(r), "hello");
+        assert_eq!(
+            &*FormStr::<T, Rc<str>>::try_from(Arc::from("A")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_literal() {
+        const NAME: StaticFormStr<Lower<MaxLen<5, Trim<()>>>> =
+            form_str!(Lower<MaxLen<5, Trim<()>>>, "john");
+        const FILE: StaticFormStr<MinLen<1, Filename>> = form_str!(MinLen<1, Filename>, "a b.txt");
+
+        assert_eq!(&*NAME, "john");
+        assert_eq!(NAME, FormStr::new("John").unwrap());
+        assert_eq!(&*FILE, "a b.txt");
+        assert!(matches!(NAME.clone().0, Cow::Borrowed(_)));
+
+        type T = Upper<MaxLen<2, Trim<()>, Utf16>>;
+
+        assert!(T::RULES.check("😀"));
+        assert!(!T::RULES.check("😀A"));
+        assert!(!T::RULES.check("a"));
+        assert!(!T::RULES.check(" A"));
+        assert!(!T::RULES.check("ǅ"));
+        assert!(!Lower::<()>::RULES.check("ǅ"));
+        assert!(!Lower::<()>::RULES.check("ᾈ"));
+        assert!(Lower::<()>::RULES.check("ǆ"));
+        assert!(Upper::<()>::RULES.check("Ǆ"));
+
+        // a literal accepted by the rules is unchanged by the format.
+        for c in (0..=char::MAX as u32).filter_map(char::from_u32) {
+            let s = c.to_string();
+
+            if Lower::<()>::RULES.check(&s) {
+                assert_eq!(s.to_lowercase(), s, "{c:?}");
+            }
+
+            if Upper::<()>::RULES.check(&s) {
+                assert_eq!(s.to_uppercase(), s, "{c:?}");
+            }
+        }
+        assert!(!Filename::RULES.check("con"));
+        assert!(!Filename::RULES.check("con .txt"));
+        assert!(!Filename::RULES.check("LPT¹"));
+        assert!(!Filename::RULES.check("a."));
+        assert!(!Filename::RULES.check("a:b"));
+
+        // the same length limit as FormStr::<Filename>::new.
+        let long = "😀".repeat(128);
+
+        assert!(Filename::RULES.check(&long[4..]));
+        assert!(FormStr::<Filename>::new(&long[4..]).is_ok());
+        assert!(!Filename::RULES.check(&long));
+        assert!(FormStr::<Filename>::new(&long).is_err());
+        assert!(!MinLen::<2, (), Bytes>::RULES.check("a"));
+        assert!(MinLen::<2, (), Bytes>::RULES.check("é"));
+    }
+
+    #[test]
+    fn test_len_units() {
+        assert!(FormStr::<MaxLen<3, ()>>::new("été").is_ok());
+        assert!(FormStr::<MaxLen<3, (), Bytes>>::new("été").is_err());
+        assert!(FormStr::<MaxLen<2, (), Graphemes>>::new("e\u{301}e\u{301}").is_ok());
+        assert!(FormStr::<MaxLen<2, (), Utf16>>::new("😀").is_ok());
+        assert!(FormStr::<MaxLen<2, (), Utf16>>::new("a😀").is_err());
+
+        assert!(FormStr::<MinLen<2, ()>>::new("😀").is_err());
+        assert!(FormStr::<MinLen<2, (), Utf16>>::new("😀").is_ok());
+        assert!(FormStr::<MinLen<2, (), Graphemes>>::new("e\u{301}").is_err());
+    }
+
+    #[test]
+    fn test_truncate() {
+        type T = Truncate<3, Trim<()>>;
+
+        assert_eq!(&*FormStr::<T>::new(" Hello ").unwrap(), "Hel");
+        assert_eq!(&*FormStr::<T>::new(" Hel ").unwrap(), "Hel");
+        assert_eq!(
+            &*FormStr::<Truncate<4, (), Bytes>>::new("été").unwrap(),
+            "ét"
+        );
+        assert_eq!(&*FormStr::<Truncate<1, (), Utf16>>::new("😀").unwrap(), "");
+
+        type E = TruncateEllipsis<4, ()>;
+
+        assert_eq!(&*FormStr::<E>::new("Hello").unwrap(), "Hel…");
+        assert_eq!(&*FormStr::<E>::new("Hell").unwrap(), "Hell");
+        assert_eq!(&*FormStr::<TruncateEllipsis<0, ()>>::new("Hi").unwrap(), "");
+        assert_eq!(
+            &*FormStr::<TruncateEllipsis<6, (), Bytes>>::new("Hello!!").unwrap(),
+            "Hel…"
+        );
+    }
+
+    #[test]
+    fn test_upper() {
+        type U = Upper<()>;
+
+        assert_eq!(&*FormStr::<U>::new("Hello").unwrap(), "HELLO");
+    }
+}
+
+/// Serde helpers for an `Option<FormStr<F>>` treating an empty string as `None`.
+///
+/// The string is checked with [NoneIfEmpty](crate::NoneIfEmpty) before and after formatting, so a
+/// `Trim` format also turns a blank string into `None`. `None` is serialized as a unit.
+///
+/// # Example
+/// ```
+/// use serde::Deserialize;
+/// use str_utils::form_str::{formats::Trim, FormStr};
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     #[serde(default, with = "str_utils::form_str::none_if_empty")]
+///     nickname: Option<FormStr<Trim<()>>>,
+/// }
+///
+/// let user: User = serde_json::from_str(r#"{ "nickname": "  " }"#).unwrap();
+///
+/// assert!(user.nickname.is_none());
+/// ```
+#[cfg(feature = "serde")]
+pub mod none_if_empty {
+    use super::{FormStr, FormStrSeed, Format, Storage};
+    use crate::NoneIfEmpty;
+    use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn deserialize<'de, D, F, S>(deserializer: D) -> Result<Option<FormStr<F, S>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        F: Default + Format,
+        S: Storage,
+    {
+        let Some(s) = Option::<String>::deserialize(deserializer)?.none_if_empty() else {
+            return Ok(None);
+        };
+
+        Ok(FormStrSeed::<F, S>::new(F::default())
+            .visit_string::<D::Error>(s)?
+            .none_if_empty())
+    }
+
+    pub fn serialize<Ser, F, S>(
+        value: &Option<FormStr<F, S>>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+        F: Format,
+        S: Storage,
+    {
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(all(feature = "serde", test))]
+#[test]
+fn deserialize_none_if_empty() {
+    use formats::Trim;
+
+    #[derive(serde::Deserialize, serde::Serialize)]
+    struct User {
+        #[serde(with = "none_if_empty")]
+        name: Option<FormStr<Trim<()>>>,
+    }
+
+    let user: User = serde_json::from_str(r#"{ "name": "" }"#).unwrap();
+    assert!(user.name.is_none());
+
+    let user: User = serde_json::from_str(r#"{ "name": null }"#).unwrap();
+    assert!(user.name.is_none());
+
+    let user: User = serde_json::from_str(r#"{ "name": " Bob " }"#).unwrap();
+    assert_eq!(user.name.as_deref(), Some("Bob"));
+    assert_eq!(serde_json::to_string(&user).unwrap(), r#"{"name":"Bob"}"#);
+}
+
 #[cfg(all(feature = "serde", test))]
 #[test]
 fn deserialize() {
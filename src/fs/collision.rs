@@ -0,0 +1,363 @@
+use super::{filename::stem_len, FilenamePolicy, SubPath, SubPathBuf};
+use crate::str_ci::StringCi;
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+use unidecode::unidecode_char;
+
+/// Define when two names refer to the same file, see [CollisionDetector].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CollisionPolicy {
+    /// Names collide when they are identical.
+    Exact,
+    /// Case insensitive, like [StrCi](crate::str_ci::StrCi).
+    #[default]
+    Ci,
+    /// Names collide when they have the same NFC normalization, such as "é" and "e\u{301}".
+    Normalized,
+    /// Case and normalization insensitive, like APFS and HFS+.
+    NormalizedCi,
+    /// Accent and case insensitive, see [EqExt::eq_ai_ci](crate::cmp::EqExt::eq_ai_ci).
+    AiCi,
+}
+
+impl CollisionPolicy {
+    /// The key shared by the names which collide.
+    fn key(self, s: &str) -> Key {
+        match self {
+            Self::Exact => Key::Folded(s.to_string()),
+            Self::Ci => Key::Ci(StringCi(s.to_string())),
+            Self::Normalized => Key::Folded(s.nfc().collect()),
+            Self::NormalizedCi => Key::Folded(s.nfd().flat_map(char::to_lowercase).nfc().collect()),
+            Self::AiCi => Key::Folded(
+                s.chars()
+                    .flat_map(|c| unidecode_char(c).chars())
+                    .flat_map(char::to_lowercase)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// The key of an entry: a folded name, or a name compared like [StrCi](crate::str_ci::StrCi).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Key {
+    Ci(StringCi),
+    Folded(String),
+}
+
+impl From<FilenamePolicy> for CollisionPolicy {
+    fn from(value: FilenamePolicy) -> Self {
+        match value {
+            FilenamePolicy::Windows => Self::Ci,
+            FilenamePolicy::Posix | FilenamePolicy::PortablePosix => Self::Exact,
+            FilenamePolicy::MacOs | FilenamePolicy::Strictest => Self::NormalizedCi,
+        }
+    }
+}
+
+/// A conflict reported by [CollisionDetector::insert].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Collision {
+    /// A file is expected where a folder exists, or the opposite. Contains the existing path.
+    DirFile(SubPathBuf),
+    /// A file or a folder with the same name under the policy exists, such as "A.txt" and
+    /// "a.txt". Contains the existing path.
+    Name(SubPathBuf),
+}
+
+/// Detects the paths referring to the same file under a [CollisionPolicy], such as the
+/// files of an uploaded folder tree.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{Collision, CollisionDetector, CollisionPolicy, SubPathBuf};
+///
+/// let path = |s| SubPathBuf::new(s).unwrap();
+/// let mut detector = CollisionDetector::new(CollisionPolicy::Ci);
+///
+/// assert!(detector.insert(&path("docs/Readme.txt")).is_ok());
+/// assert_eq!(
+///     detector.insert(&path("docs/README.TXT")),
+///     Err(Collision::Name(path("docs/Readme.txt")))
+/// );
+/// assert_eq!(
+///     detector.insert(&path("docs/Readme.txt/a")),
+///     Err(Collision::DirFile(path("docs/Readme.txt")))
+/// );
+/// assert_eq!(detector.suggest(&path("docs/readme.txt")), path("docs/readme (2).txt"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CollisionDetector {
+    filename_policy: Option<FilenamePolicy>,
+    policy: CollisionPolicy,
+    root: HashMap<Key, Node>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// The name as inserted.
+    name: String,
+    /// The entries of a folder, `None` for a file.
+    children: Option<HashMap<Key, Node>>,
+}
+
+impl CollisionDetector {
+    pub fn new(policy: CollisionPolicy) -> Self {
+        Self {
+            filename_policy: None,
+            policy,
+            root: HashMap::new(),
+        }
+    }
+
+    /// Detects the collisions of a platform, the names suggested by
+    /// [CollisionDetector::suggest] are truncated to the maximum length of the policy.
+    pub fn with_filename_policy(policy: FilenamePolicy) -> Self {
+        Self {
+            filename_policy: Some(policy),
+            ..Self::new(policy.into())
+        }
+    }
+
+    /// Returns the collision the file would cause, without inserting it.
+    pub fn check(&self, path: &SubPath) -> Result<(), Collision> {
+        self.find(path, false).map_or(Ok(()), |(_, c)| Err(c))
+    }
+
+    /// Returns the collision the folder would cause, without inserting it.
+    pub fn check_dir(&self, path: &SubPath) -> Result<(), Collision> {
+        self.find(path, true).map_or(Ok(()), |(_, c)| Err(c))
+    }
+
+    /// Returns the segment index of the first collision and the collision.
+    fn find(&self, path: &SubPath, is_dir: bool) -> Option<(usize, Collision)> {
+        let mut entries = &self.root;
+        let mut existing = String::new();
+        let last = path.segments().count() - 1;
+
+        for (i, segment) in path.segments().enumerate() {
+            let node = entries.get(&self.policy.key(segment))?;
+
+            if !existing.is_empty() {
+                existing.push('/');
+            }
+
+            existing.push_str(&node.name);
+
+            match &node.children {
+                // the same folder can be shared by many paths.
+                Some(children) if i < last || is_dir => {
+                    if node.name != segment {
+                        return Some((i, Collision::Name(SubPathBuf(existing))));
+                    }
+
+                    entries = children;
+                }
+                None if i == last && !is_dir => {
+                    return Some((i, Collision::Name(SubPathBuf(existing))));
+                }
+                _ => return Some((i, Collision::DirFile(SubPathBuf(existing)))),
+            }
+        }
+
+        None
+    }
+
+    /// Insert a file, unless it collides with a previous path.
+    pub fn insert(&mut self, path: &SubPath) -> Result<(), Collision> {
+        self.insert_node(path, false)
+    }
+
+    /// Insert a folder, unless it collides with a previous path. Inserting an existing
+    /// folder again is accepted.
+    pub fn insert_dir(&mut self, path: &SubPath) -> Result<(), Collision> {
+        self.insert_node(path, true)
+    }
+
+    fn insert_node(&mut self, path: &SubPath, is_dir: bool) -> Result<(), Collision> {
+        if let Some((_, c)) = self.find(path, is_dir) {
+            return Err(c);
+        }
+
+        let mut entries = &mut self.root;
+        let last = path.segments().count() - 1;
+
+        for (i, segment) in path.segments().enumerate() {
+            let node = entries
+                .entry(self.policy.key(segment))
+                .or_insert_with(|| Node {
+                    name: segment.to_string(),
+                    children: (i < last || is_dir).then(HashMap::new),
+                });
+
+            match &mut node.children {
+                Some(children) => entries = children,
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn policy(&self) -> CollisionPolicy {
+        self.policy
+    }
+
+    /// Returns the path itself when it can be inserted as a file, otherwise renames the
+    /// colliding segment into "name (2).txt", "name (3).txt", ... until there is no
+    /// collision. With a [FilenamePolicy], the stem is truncated so the new name fits its
+    /// maximum length, keeping at least one char.
+    pub fn suggest(&self, path: &SubPath) -> SubPathBuf {
+        let Some((idx, _)) = self.find(path, false) else {
+            return path.to_owned();
+        };
+
+        let segments = path.segments().collect::<Vec<_>>();
+        let segment = segments[idx];
+        let is_file = idx == segments.len() - 1;
+
+        // keep the extension of a file, the first dot of a hidden file is not an extension.
+        let (stem, ext) = match is_file {
+            true => segment.split_at(stem_len(segment)),
+            false => (segment, ""),
+        };
+
+        for n in 2.. {
+            let mut stem = stem;
+            let mut name = format!("{stem} ({n}){ext}");
+
+            while self.filename_policy.is_some_and(|p| p.is_too_long(&name)) {
+                let Some((idx, _)) = stem.char_indices().next_back().filter(|(i, _)| *i > 0) else {
+                    break;
+                };
+
+                stem = &stem[..idx];
+                name = format!("{stem} ({n}){ext}");
+            }
+
+            let mut out = segments[..idx].join("/");
+
+            if !out.is_empty() {
+                out.push('/');
+            }
+
+            out.push_str(&name);
+
+            for s in &segments[idx + 1..] {
+                out.push('/');
+                out.push_str(s);
+            }
+
+            let out = SubPathBuf(out);
+
+            if self.find(&out, false).is_none() {
+                return out;
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+#[test]
+fn test_collision_detector() {
+    let path = |s| SubPathBuf::new(s).unwrap();
+    let mut d = CollisionDetector::new(CollisionPolicy::Ci);
+
+    assert!(d.insert(&path("a/b/c.txt")).is_ok());
+    assert!(d.insert(&path("a/b/d.txt")).is_ok());
+    assert!(d.insert_dir(&path("a/b")).is_ok());
+    assert!(d.insert_dir(&path("a/e")).is_ok());
+    assert_eq!(
+        d.insert(&path("a/b/c.txt")),
+        Err(Collision::Name(path("a/b/c.txt")))
+    );
+    assert_eq!(d.insert(&path("A/x.txt")), Err(Collision::Name(path("a"))));
+    assert_eq!(
+        d.insert(&path("a/b/C.TXT")),
+        Err(Collision::Name(path("a/b/c.txt")))
+    );
+    assert_eq!(d.insert(&path("a/b")), Err(Collision::DirFile(path("a/b"))));
+    assert_eq!(
+        d.insert_dir(&path("a/b/c.txt")),
+        Err(Collision::DirFile(path("a/b/c.txt")))
+    );
+    assert_eq!(
+        d.insert(&path("a/b/c.txt/x")),
+        Err(Collision::DirFile(path("a/b/c.txt")))
+    );
+
+    // a failed insertion does not leave partial folders.
+    assert!(d.insert(&path("f/g/H.txt")).is_ok());
+    assert!(d.insert(&path("f/g/h.txt/i")).is_err());
+    assert!(d.check(&path("f/g/h.txt")).is_err());
+    assert!(d.check(&path("f/g/i.txt")).is_ok());
+
+    assert_eq!(d.suggest(&path("a/b/new.txt")), path("a/b/new.txt"));
+    assert_eq!(d.suggest(&path("a/b/C.txt")), path("a/b/C (2).txt"));
+    assert!(d.insert(&path("a/b/c (2).txt")).is_ok());
+    assert_eq!(d.suggest(&path("a/b/C.txt")), path("a/b/C (3).txt"));
+    assert_eq!(d.suggest(&path("a/b")), path("a/b (2)"));
+    assert_eq!(
+        d.suggest(&path("a/b/c.txt/x.txt")),
+        path("a/b/c.txt (2)/x.txt")
+    );
+    assert_eq!(d.suggest(&path("A/.env")), path("A (2)/.env"));
+    assert!(d.insert(&path("a/.env")).is_ok());
+    assert_eq!(d.suggest(&path("a/.env")), path("a/.env (2)"));
+    assert_eq!(d.suggest(&path("a/x.tar.gz")), path("a/x.tar.gz"));
+    assert!(d.insert(&path("a/x.tar.gz")).is_ok());
+    assert_eq!(d.suggest(&path("a/X.tar.gz")), path("a/X (2).tar.gz"));
+}
+
+#[test]
+fn test_collision_policies() {
+    let path = |s| SubPathBuf::new(s).unwrap();
+    let collide = |policy: CollisionPolicy, a, b| {
+        let mut d = CollisionDetector::new(policy);
+        d.insert(&path(a)).unwrap();
+        d.insert(&path(b)).is_err()
+    };
+
+    assert!(!collide(CollisionPolicy::Exact, "a.txt", "A.txt"));
+    assert!(!collide(CollisionPolicy::Exact, "\u{e9}", "e\u{301}"));
+    assert!(collide(CollisionPolicy::Ci, "été", "ÉTÉ"));
+    // like StrCi, chars are compared one by one, "İ" is not its lowercase "i\u{307}".
+    assert!(!collide(CollisionPolicy::Ci, "İ", "i\u{307}"));
+    assert!(!collide(CollisionPolicy::Ci, "\u{e9}", "e\u{301}"));
+    assert!(collide(CollisionPolicy::Normalized, "\u{e9}", "e\u{301}"));
+    assert!(!collide(CollisionPolicy::Normalized, "\u{e9}", "\u{c9}"));
+    assert!(collide(CollisionPolicy::NormalizedCi, "\u{e9}", "E\u{301}"));
+    assert!(collide(CollisionPolicy::AiCi, "Été.txt", "ete.TXT"));
+    assert!(!collide(CollisionPolicy::AiCi, "a.txt", "b.txt"));
+
+    assert_eq!(
+        CollisionPolicy::from(FilenamePolicy::MacOs),
+        CollisionPolicy::NormalizedCi
+    );
+}
+
+#[test]
+fn test_suggest_max_len() {
+    let path = |s: &str| SubPathBuf::new(s).unwrap();
+    let long = "é".repeat(125);
+    let mut d = CollisionDetector::with_filename_policy(FilenamePolicy::Posix);
+
+    assert_eq!(d.policy(), CollisionPolicy::Exact);
+    assert!(d.insert(&path(&format!("{long}.txt"))).is_ok());
+
+    let suggested = d.suggest(&path(&format!("{long}.txt")));
+    let name = suggested.segments().next_back().unwrap();
+
+    assert_eq!(name, format!("{} (2).txt", "é".repeat(123)));
+    assert!(!FilenamePolicy::Posix.is_too_long(name));
+
+    // without a filename policy, the name is not truncated.
+    let mut d = CollisionDetector::new(CollisionPolicy::Exact);
+
+    assert!(d.insert(&path(&format!("{long}.txt"))).is_ok());
+    assert_eq!(
+        d.suggest(&path(&format!("{long}.txt"))),
+        path(&format!("{long} (2).txt"))
+    );
+}
@@ -0,0 +1,272 @@
+use super::{validate_filename, FsError, Result, SubPathBuf};
+use crate::cmp::EqExt;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+    path::Path,
+    str::FromStr,
+};
+
+/// A filename validated by [validate_filename], such as "archive.tar.gz".
+///
+/// The stem is the part before the first dot, the extensions follow it. The first dot of a
+/// hidden file such as ".gitignore" is part of the stem.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::Filename;
+///
+/// let name = Filename::new("archive.tar.gz").unwrap();
+///
+/// assert_eq!(name.stem(), "archive");
+/// assert_eq!(name.extension(), Some("gz"));
+/// assert_eq!(name.extensions().collect::<Vec<_>>(), ["tar", "gz"]);
+/// assert!(name.has_extension("TAR.GZ"));
+/// assert_eq!(name.with_extension("tgz").unwrap().as_str(), "archive.tar.tgz");
+/// assert!(name.with_stem("con").is_err());
+/// ```
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Filename(String);
+
+impl Filename {
+    /// Validate and trim a filename, see [validate_filename].
+    pub fn new(s: &str) -> Result<Self> {
+        validate_filename(s).map(|s| Self(s.to_string()))
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The last extension, without the dot.
+    pub fn extension(&self) -> Option<&str> {
+        self.all_extensions()
+            .map(|e| e.rsplit_once('.').map_or(e, |(_, e)| e))
+    }
+
+    /// Every extension, from the first to the last.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.all_extensions().into_iter().flat_map(|e| e.split('.'))
+    }
+
+    /// The part after the stem, without the dot, such as "tar.gz".
+    fn all_extensions(&self) -> Option<&str> {
+        let stem = self.stem();
+        self.0.get(stem.len() + 1..)
+    }
+
+    /// Returns true if the name ends with the extensions, compared with [EqExt::eq_ci]. A
+    /// leading dot is ignored, "pdf", ".PDF" and "tar.gz" are accepted.
+    pub fn has_extension(&self, ext: &str) -> bool {
+        let ext = ext.strip_prefix('.').unwrap_or(ext);
+
+        match self.all_extensions() {
+            Some(all) if !ext.is_empty() && all.len() >= ext.len() => {
+                let (rest, end) = all
+                    .split_at_checked(all.len() - ext.len())
+                    .unwrap_or_default();
+                (rest.is_empty() || rest.ends_with('.')) && end.eq_ci(ext)
+            }
+            _ => false,
+        }
+    }
+
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// The part before the first dot.
+    pub fn stem(&self) -> &str {
+        &self.0[..stem_len(&self.0)]
+    }
+
+    /// Replace the last extension, or append it when there is none. An empty extension
+    /// removes the last one.
+    pub fn with_extension(&self, ext: &str) -> Result<Self> {
+        let ext = ext.strip_prefix('.').unwrap_or(ext);
+
+        let base = match self.extension() {
+            Some(old) => &self.0[..self.0.len() - old.len() - 1],
+            None => &self.0,
+        };
+
+        if ext.is_empty() {
+            Self::try_from(base.to_string())
+        } else {
+            Self::try_from(format!("{base}.{ext}"))
+        }
+    }
+
+    /// Replace the stem, keeping the extensions. An empty or blank stem is rejected with
+    /// [FsError::EmptySegment] since the first extension would become the stem.
+    pub fn with_stem(&self, stem: &str) -> Result<Self> {
+        if stem.trim().is_empty() {
+            return Err(FsError::EmptySegment);
+        }
+
+        Self::try_from(format!("{stem}{}", &self.0[self.stem().len()..]))
+    }
+
+    /// Append a suffix to the stem, such as "report (1).pdf".
+    pub fn with_suffix(&self, suffix: &str) -> Result<Self> {
+        let stem = self.stem();
+        Self::try_from(format!("{stem}{suffix}{}", &self.0[stem.len()..]))
+    }
+}
+
+/// The byte length of the part before the first dot, ignoring the dot starting a hidden file.
+pub(super) fn stem_len(name: &str) -> usize {
+    // a dot byte is always a dot char in UTF-8.
+    match name.bytes().skip(1).position(|b| b == b'.') {
+        Some(idx) => idx + 1,
+        None => name.len(),
+    }
+}
+
+impl AsRef<Path> for Filename {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.0)
+    }
+}
+
+impl AsRef<str> for Filename {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for Filename {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Deref for Filename {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for Filename {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<Filename> for String {
+    #[inline]
+    fn from(value: Filename) -> Self {
+        value.0
+    }
+}
+
+impl From<Filename> for SubPathBuf {
+    /// A filename is a sub path with a single segment.
+    #[inline]
+    fn from(value: Filename) -> Self {
+        SubPathBuf(value.0)
+    }
+}
+
+impl FromStr for Filename {
+    type Err = FsError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for Filename {
+    type Error = FsError;
+
+    #[inline]
+    fn try_from(value: &str) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for Filename {
+    type Error = FsError;
+
+    fn try_from(mut value: String) -> Result<Self> {
+        let v = validate_filename(&value)?;
+
+        // reuse the allocation when the name is already trimmed.
+        if v.len() != value.len() {
+            value = v.to_string();
+        }
+
+        Ok(Self(value))
+    }
+}
+
+#[test]
+fn test_filename() {
+    let name = |s| Filename::new(s).unwrap();
+
+    for (input, stem, ext, exts) in [
+        ("archive.tar.gz", "archive", Some("gz"), &["tar", "gz"][..]),
+        ("a.txt", "a", Some("txt"), &["txt"]),
+        ("README", "README", None, &[]),
+        (".gitignore", ".gitignore", None, &[]),
+        (".env.local", ".env", Some("local"), &["local"]),
+        (" b . txt ", "b ", Some(" txt"), &[" txt"]),
+    ] {
+        let n = name(input);
+
+        assert_eq!(n.stem(), stem, "{input}");
+        assert_eq!(n.extension(), ext, "{input}");
+        assert_eq!(n.extensions().collect::<Vec<_>>(), exts, "{input}");
+    }
+
+    let n = name("Archive.TAR.gz");
+
+    assert!(n.has_extension("gz"));
+    assert!(n.has_extension(".GZ"));
+    assert!(n.has_extension("tar.gz"));
+    assert!(!n.has_extension("ar.gz"));
+    assert!(!n.has_extension("archive.tar.gz"));
+    assert!(!n.has_extension(""));
+    assert!(!name(".gitignore").has_extension("gitignore"));
+
+    assert_eq!(n.with_extension("zip").unwrap().as_str(), "Archive.TAR.zip");
+    assert_eq!(
+        n.with_extension(".zip").unwrap().as_str(),
+        "Archive.TAR.zip"
+    );
+    assert_eq!(n.with_extension("").unwrap().as_str(), "Archive.TAR");
+    assert_eq!(name("a").with_extension("txt").unwrap().as_str(), "a.txt");
+    assert_eq!(n.with_extension("z?p"), Err(FsError::InvalidChar('?')));
+    assert_eq!(name("a").with_extension("b."), Err(FsError::EndsWithDot));
+
+    assert_eq!(n.with_stem("backup").unwrap().as_str(), "backup.TAR.gz");
+    assert_eq!(n.with_stem("nul"), Err(FsError::ReservedName("NUL")));
+    assert_eq!(n.with_stem(" backup ").unwrap().as_str(), "backup .TAR.gz");
+    assert_eq!(n.with_stem(""), Err(FsError::EmptySegment));
+    assert_eq!(n.with_stem("  "), Err(FsError::EmptySegment));
+    assert_eq!(name("été.txt").stem(), "été");
+
+    assert_eq!(
+        name("report.pdf").with_suffix(" (1)").unwrap().as_str(),
+        "report (1).pdf"
+    );
+    assert_eq!(
+        name("a.txt").with_suffix("/b"),
+        Err(FsError::InvalidChar('/'))
+    );
+
+    assert_eq!(
+        Filename::try_from(" a.txt ".to_string()).unwrap().as_str(),
+        "a.txt"
+    );
+    assert_eq!("con".parse::<Filename>(), Err(FsError::ReservedName("CON")));
+    assert_eq!(SubPathBuf::from(name("a.txt")).file_name(), "a.txt");
+}
@@ -0,0 +1,877 @@
+mod collision;
+mod filename;
+mod policy;
+mod sub_path;
+mod unique;
+
+pub use collision::{Collision, CollisionDetector, CollisionPolicy};
+pub use filename::Filename;
+pub use policy::*;
+pub use sub_path::{SubPath, SubPathBuf};
+pub use unique::{unique_filename, unique_filename_with, UniqueOptions};
+
+use crate::{url, StrUtilsExt};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display, Formatter},
+};
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FsError {
+    /// A `:` after the name refers to an NTFS alternate data stream, such as "file.txt:secret".
+    AlternateDataStream,
+    /// A UNC or device namespace prefix, such as `\\server\share` or `\\?\C:\`.
+    DevicePrefix,
+    /// An empty segment in a path, such as "a//b", or an empty stem in a filename.
+    EmptySegment,
+    /// A percent-encoded char hiding a separator, a dot or an invalid char, such as "%2e%2e".
+    EncodedChar(char),
+    EndsWithDot,
+    Home,
+    InvalidChar(char),
+    /// A zero-width or formatting char which is not rendered.
+    InvisibleChar(char),
+    /// A char whose NFKC normalization is a separator, a dot or an invalid char, such as the
+    /// full-width solidus "／".
+    NormalizedChar(char),
+    ReservedName(&'static str),
+    /// An absolute path, or a drive prefix such as "C:", which escapes the folder.
+    Root,
+    StartsWithHyphen,
+    /// The filename is longer than the maximum length of the [FilenamePolicy].
+    TooLong(usize),
+}
+
+pub type Result<T> = std::result::Result<T, FsError>;
+
+impl Debug for FsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlternateDataStream => f.write_str("filename refer to an alternate data stream"),
+            Self::DevicePrefix => f.write_str("path starts with a device prefix"),
+            Self::EmptySegment => f.write_str("empty segment"),
+            Self::EncodedChar(c) => write!(f, "path contains an encoded {c:?}"),
+            Self::EndsWithDot => f.write_str("filename ends with dot"),
+            Self::Home => f.write_str("path refer to ~"),
+            Self::InvalidChar(c) => write!(f, "invalid filename char {c}"),
+            Self::InvisibleChar(c) => write!(f, "invisible char {c:?}"),
+            Self::NormalizedChar(c) => write!(f, "char {c} normalized into a path char"),
+            Self::ReservedName(s) => write!(f, "reserved file name {s}"),
+            Self::Root => f.write_str("path cannot be root"),
+            Self::StartsWithHyphen => f.write_str("filename starts with hyphen"),
+            Self::TooLong(l) => write!(f, "filename longer than {l}"),
+        }
+    }
+}
+
+impl Display for FsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// Apply the validation of the [validate_sub_path] function. If it works, it format
+/// the path by triming segment and separating them with `/`.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::format_sub_path;
+///
+/// assert_eq!(format_sub_path(" a / b\\c.txt ").unwrap(), "a/b/c.txt");
+/// ```
+pub fn format_sub_path(s: &str) -> Result<String> {
+    validate_sub_path(s)?;
+
+    let mut out = String::with_capacity(s.len());
+
+    for s in s.trim().split(['/', '\\']) {
+        if !out.is_empty() {
+            out.push('/');
+        }
+
+        out.push_str(s.trim());
+    }
+
+    Ok(out)
+}
+
+/// Options of [sanitize_filename].
+#[derive(Clone, Copy, Debug)]
+pub struct SanitizeOptions {
+    /// The maximum length in bytes, 255 by default. A non empty name is never sanitized into
+    /// an empty one, so a limit of 0 is treated as 1.
+    pub max_bytes: usize,
+    /// The char replacing an invalid char, `_` by default. Invalid chars are removed when `None`.
+    pub replacement: Option<char>,
+    /// The suffix appended to a reserved device name such as "CON", `_` by default. The
+    /// control chars, the invalid chars and the dots are removed from the suffix, as well as
+    /// its trailing whitespace chars since Windows ignores them, `_` is used when nothing is
+    /// left.
+    pub reserved_suffix: &'static str,
+    /// Remove the accents and transliterate the chars into ascii using [StrUtilsExt::no_accent].
+    pub transliterate: bool,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes: 255,
+            replacement: Some('_'),
+            reserved_suffix: "_",
+            transliterate: false,
+        }
+    }
+}
+
+/// Repair a filename so it passes [validate_filename] instead of rejecting it.
+///
+/// Control chars are removed, invalid chars are replaced, the name is trimmed of spaces and
+/// trailing dots, a suffix is appended to reserved device names (also when followed by an
+/// extension such as "CON.txt") and the name is truncated to the byte limit without
+/// splitting a char, keeping the extension when possible. A non empty name that would
+/// become empty is replaced by `_`.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{sanitize_filename, SanitizeOptions};
+///
+/// let options = SanitizeOptions::default();
+///
+/// assert_eq!(sanitize_filename("report: 2024/05?.pdf", options), "report_ 2024_05_.pdf");
+/// assert_eq!(sanitize_filename("con.txt", options), "con_.txt");
+/// assert_eq!(sanitize_filename("notes. . ", options), "notes");
+///
+/// let options = SanitizeOptions { transliterate: true, ..options };
+///
+/// assert_eq!(sanitize_filename("Été.txt", options), "Ete.txt");
+/// ```
+pub fn sanitize_filename(s: &str, options: SanitizeOptions) -> Cow<'_, str> {
+    let transliterated;
+
+    let src = if options.transliterate {
+        transliterated = s.no_accent();
+        &transliterated
+    } else {
+        s
+    };
+
+    let replacement = options
+        .replacement
+        .filter(|c| !is_invalid_filename_char(*c));
+
+    let mut out = String::with_capacity(src.len());
+
+    for c in src.chars() {
+        if c.is_control() {
+            continue;
+        }
+
+        if is_invalid_filename_char(c) {
+            out.extend(replacement);
+        } else {
+            out.push(c);
+        }
+    }
+
+    trim_filename(&mut out);
+
+    let max_bytes = options.max_bytes.max(1);
+
+    if out.len() > max_bytes {
+        truncate_filename(&mut out, max_bytes);
+    }
+
+    if reserved_name(&out).is_some() {
+        let suffix = reserved_suffix(options.reserved_suffix);
+
+        // keep room for the suffix.
+        if out.len() + suffix.len() > max_bytes {
+            truncate_filename(&mut out, max_bytes.saturating_sub(suffix.len()));
+        }
+
+        out.insert_str(stem(&out).len(), &suffix);
+    }
+
+    if out.is_empty() && !s.is_empty() {
+        out.push('_');
+    }
+
+    if out == s {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+/// Returns the suffix without the chars which would make the name invalid or reserved again,
+/// such as "con.txt" with the suffix "." or " ".
+fn reserved_suffix(suffix: &str) -> String {
+    let mut out = suffix
+        .chars()
+        .filter(|c| !c.is_control() && !is_invalid_filename_char(*c) && *c != '.')
+        .collect::<String>();
+
+    out.truncate(out.trim_end().len());
+
+    if out.is_empty() {
+        out.push('_');
+    }
+
+    out
+}
+
+/// Remove leading whitespace chars and trailing whitespace chars and dots.
+fn trim_filename(s: &mut String) {
+    let len = s
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+        .len();
+    s.truncate(len);
+
+    let start = s.len() - s.trim_start().len();
+    s.drain(..start);
+}
+
+/// Truncate a filename to `max_bytes`, keeping the extension when it fits.
+fn truncate_filename(s: &mut String, max_bytes: usize) {
+    let ext = match s.rfind('.') {
+        Some(idx) if idx > 0 && s.len() - idx < max_bytes => s.split_off(idx),
+        _ => String::new(),
+    };
+
+    let len = s.truncate_bytes(max_bytes - ext.len()).len();
+
+    s.truncate(len);
+    trim_filename(s);
+    s.push_str(&ext);
+}
+
+/// Returns the part before the first dot, without trailing whitespace chars.
+fn stem(s: &str) -> &str {
+    s.split('.').next().unwrap_or_default().trim_end()
+}
+
+/// Format a filename such as "test.txt" and validate it.
+///
+/// Since filename must always be trimmed, the function returns the trimmed input string.
+pub fn validate_filename(s: &str) -> Result<&str> {
+    validate_filename_with(s, FilenamePolicy::Windows)
+}
+
+/// Validate a filename like [validate_filename] using the rules of a [FilenamePolicy].
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{validate_filename_with, FilenamePolicy};
+///
+/// assert!(validate_filename_with("a:b", FilenamePolicy::Posix).is_ok());
+/// assert!(validate_filename_with("a:b", FilenamePolicy::Windows).is_err());
+/// ```
+pub fn validate_filename_with(s: &str, policy: FilenamePolicy) -> Result<&str> {
+    let mut errors = Errors::first();
+    let s = check_filename(s, policy, &mut errors);
+    errors.into_result(s)
+}
+
+/// Validate a filename like [validate_filename], returning every error instead of the first one.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{validate_filename_all, FsError};
+///
+/// assert_eq!(
+///     validate_filename_all("a<b>."),
+///     [FsError::InvalidChar('<'), FsError::InvalidChar('>'), FsError::EndsWithDot]
+/// );
+/// ```
+pub fn validate_filename_all(s: &str) -> Vec<FsError> {
+    validate_filename_all_with(s, FilenamePolicy::Windows)
+}
+
+/// Validate a filename like [validate_filename_with], returning every error instead of the
+/// first one.
+pub fn validate_filename_all_with(s: &str, policy: FilenamePolicy) -> Vec<FsError> {
+    let mut errors = Errors::all();
+    check_filename(s, policy, &mut errors);
+    errors.list
+}
+
+fn check_filename<'a>(s: &'a str, policy: FilenamePolicy, errors: &mut Errors) -> &'a str {
+    let start = s.len() - s.trim_start().len();
+
+    for (idx, c) in s.char_indices() {
+        if policy.is_invalid_char(c) {
+            let e = match c {
+                // "C:" refers to a drive, "C:a" to a file in the current folder of that drive.
+                ':' if idx == start + 1
+                    && s.as_bytes()[start].is_ascii_alphabetic()
+                    && policy.has_data_streams() =>
+                {
+                    FsError::Root
+                }
+                ':' if idx > start && policy.has_data_streams() => FsError::AlternateDataStream,
+                _ => FsError::InvalidChar(c),
+            };
+
+            if !errors.push(e) {
+                return s;
+            }
+        }
+    }
+
+    // files must always be trimmed.
+    let s = s.trim();
+
+    if policy.rejects_trailing_dot() && s.ends_with('.') && !errors.push(FsError::EndsWithDot) {
+        return s;
+    }
+
+    if policy.rejects_leading_hyphen()
+        && s.starts_with('-')
+        && !errors.push(FsError::StartsWithHyphen)
+    {
+        return s;
+    }
+
+    if policy.is_too_long(s) && !errors.push(FsError::TooLong(policy.max_len().len)) {
+        return s;
+    }
+
+    if let Some(reserved) = policy.reserved_name(s) {
+        errors.push(FsError::ReservedName(reserved));
+    }
+
+    s
+}
+
+/// Collects the validation errors, stopping at the first one unless all are requested.
+struct Errors {
+    all: bool,
+    list: Vec<FsError>,
+}
+
+impl Errors {
+    fn all() -> Self {
+        Self {
+            all: true,
+            list: Vec::new(),
+        }
+    }
+
+    fn first() -> Self {
+        Self {
+            all: false,
+            list: Vec::new(),
+        }
+    }
+
+    fn into_result<T>(mut self, value: T) -> Result<T> {
+        match self.list.pop() {
+            Some(e) => Err(e),
+            None => Ok(value),
+        }
+    }
+
+    /// Adds an error, returns true if the validation must go on.
+    fn push(&mut self, e: FsError) -> bool {
+        if !self.list.contains(&e) {
+            self.list.push(e);
+        }
+
+        self.all
+    }
+}
+
+/// Chars never accepted in a filename, in addition to control chars.
+pub(crate) const INVALID_FILENAME_CHARS: [char; 9] = ['<', '>', ':', '"', '|', '?', '*', '/', '\\'];
+
+/// Chars never accepted in a sub path, in addition to control chars. Unlike a filename, the
+/// path separators are accepted.
+pub(crate) const INVALID_SUB_PATH_CHARS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+/// Device names reserved by Windows, even when followed by an extension such as "CON.txt".
+pub(crate) const RESERVED_NAMES: [&str; 32] = [
+    "CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$", "COM0", "COM1", "COM2", "COM3", "COM4",
+    "COM5", "COM6", "COM7", "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3",
+    "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// Returns true if the char is not accepted in a filename.
+pub(crate) const fn is_invalid_filename_char(c: char) -> bool {
+    let u = c as u32;
+
+    // control chars
+    if u < 0x20 || (u >= 0x7F && u <= 0x9F) {
+        return true;
+    }
+
+    let mut i = 0;
+
+    while i < INVALID_FILENAME_CHARS.len() {
+        if INVALID_FILENAME_CHARS[i] == c {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// Returns the reserved device name matching the stem of the trimmed filename, if any.
+///
+/// Windows ignores the extension and the spaces before it, so "con .txt" is "CON".
+pub(crate) const fn reserved_name(s: &str) -> Option<&'static str> {
+    let stem = stem_bytes(s.as_bytes());
+    let mut i = 0;
+
+    while i < RESERVED_NAMES.len() {
+        // only the ascii letters of reserved names have a case, an ascii case insensitive
+        // comparison is enough.
+        if stem.eq_ignore_ascii_case(RESERVED_NAMES[i].as_bytes()) {
+            return Some(RESERVED_NAMES[i]);
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+/// Returns the bytes before the first dot, without trailing spaces.
+const fn stem_bytes(b: &[u8]) -> &[u8] {
+    let mut end = 0;
+
+    while end < b.len() && b[end] != b'.' {
+        end += 1;
+    }
+
+    while end > 0 && b[end - 1] == b' ' {
+        end -= 1;
+    }
+
+    b.split_at(end).0
+}
+
+/// Validate a path to a file. The path must not be rooted, and must not contains chars allowing it to escape a folder.
+///
+/// #Example
+///
+/// ```
+/// use str_utils::fs::validate_sub_path;
+///
+/// assert!(validate_sub_path("sub_dir/file.txt").is_ok());
+/// assert!(validate_sub_path("/file.txt").is_err());
+/// assert!(validate_sub_path("./file.txt").is_err());
+/// assert!(validate_sub_path("../file.txt").is_err());
+/// assert!(validate_sub_path("~/file.txt").is_err());
+/// assert!(validate_sub_path("\\\\test\\file.txt").is_err());
+/// assert!(validate_sub_path("c:\\file.txt").is_err());
+/// ```
+pub fn validate_sub_path(s: &str) -> Result<()> {
+    let mut errors = Errors::first();
+    check_sub_path(s, &mut errors);
+    errors.into_result(())
+}
+
+/// Validate a path like [validate_sub_path], returning every error instead of the first one.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{validate_sub_path_all, FsError};
+///
+/// assert_eq!(
+///     validate_sub_path_all("/a//b?"),
+///     [FsError::Root, FsError::EmptySegment, FsError::InvalidChar('?')]
+/// );
+/// ```
+pub fn validate_sub_path_all(s: &str) -> Vec<FsError> {
+    let mut errors = Errors::all();
+    check_sub_path(s, &mut errors);
+    errors.list
+}
+
+fn check_sub_path(s: &str, errors: &mut Errors) {
+    let s = s.trim();
+
+    if (s.starts_with('/') || s.starts_with("\\")) && !errors.push(FsError::Root) {
+        return;
+    }
+
+    if s.starts_with("~/") && !errors.push(FsError::Home) {
+        return;
+    }
+
+    for s in s.split(['/', '\\']) {
+        let s = s.trim();
+
+        if s.is_empty() {
+            if !errors.push(FsError::EmptySegment) {
+                return;
+            }
+
+            continue;
+        }
+
+        let len = errors.list.len();
+
+        check_filename(s, FilenamePolicy::Windows, errors);
+
+        if !errors.all && errors.list.len() > len {
+            return;
+        }
+    }
+}
+
+/// Validate a path like [validate_sub_path], rejecting the tricks used to hide a traversal:
+/// UNC and device prefixes, invisible chars, chars normalized into separators or dots (such
+/// as "／" or "․") and percent-encoded separators or dots (such as "%2e%2e"). The path is then
+/// percent-decoded and normalized (NFKC) before the usual validation.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{validate_sub_path_hardened, FsError};
+///
+/// assert!(validate_sub_path_hardened("a/b.txt").is_ok());
+/// assert_eq!(validate_sub_path_hardened("%2e%2e/a"), Err(FsError::EncodedChar('.')));
+/// assert_eq!(validate_sub_path_hardened("..／a"), Err(FsError::NormalizedChar('／')));
+/// assert_eq!(validate_sub_path_hardened("\\\\?\\c:"), Err(FsError::DevicePrefix));
+/// ```
+pub fn validate_sub_path_hardened(s: &str) -> Result<()> {
+    let mut errors = Errors::first();
+    check_sub_path_hardened(s, &mut errors);
+    errors.into_result(())
+}
+
+/// Validate a path like [validate_sub_path_hardened], returning every error instead of the
+/// first one.
+pub fn validate_sub_path_hardened_all(s: &str) -> Vec<FsError> {
+    let mut errors = Errors::all();
+    check_sub_path_hardened(s, &mut errors);
+    errors.list
+}
+
+fn check_sub_path_hardened(s: &str, errors: &mut Errors) {
+    let s = s.trim();
+
+    if is_device_prefixed(s) && !errors.push(FsError::DevicePrefix) {
+        return;
+    }
+
+    for c in s.chars() {
+        let e = if is_invisible_char(c) {
+            FsError::InvisibleChar(c)
+        } else if !c.is_ascii() && std::iter::once(c).nfkc().any(is_path_char) {
+            FsError::NormalizedChar(c)
+        } else {
+            continue;
+        };
+
+        if !errors.push(e) {
+            return;
+        }
+    }
+
+    for (i, _) in s.match_indices('%') {
+        if let Some(b) = url::decode_hex(&s.as_bytes()[i + 1..]) {
+            if is_path_char(b as char) && !errors.push(FsError::EncodedChar(b as char)) {
+                return;
+            }
+        }
+    }
+
+    let decoded = url::percent_decode(s);
+    let normalized = String::from_utf8_lossy(&decoded)
+        .nfkc()
+        .filter(|c| !is_invisible_char(*c))
+        .collect::<String>();
+
+    if is_device_prefixed(&normalized) && !errors.push(FsError::DevicePrefix) {
+        return;
+    }
+
+    check_sub_path(&normalized, errors);
+}
+
+/// Returns true for a UNC path such as `\\server\share`, or a device namespace such as
+/// `\\?\`, `\\.\` or `\??\`.
+fn is_device_prefixed(s: &str) -> bool {
+    let b = s.as_bytes();
+    let is_sep = |i: usize| matches!(b.get(i), Some(b'/' | b'\\'));
+
+    (is_sep(0) && is_sep(1)) || (is_sep(0) && b.get(1..3) == Some(b"??") && is_sep(3))
+}
+
+/// Returns true for the ascii chars which can alter a path: separators, dots, `~`, `%` and the
+/// chars invalid in a filename.
+fn is_path_char(c: char) -> bool {
+    c.is_ascii() && (matches!(c, '.' | '~' | '%') || is_invalid_filename_char(c))
+}
+
+/// Returns true for the zero-width and formatting chars, which are not rendered.
+pub(crate) fn is_invisible_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{AD}'
+            | '\u{34F}'
+            | '\u{61C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{17B4}'..='\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{FFF0}'..='\u{FFF8}'
+            | '\u{1D173}'..='\u{1D17A}'
+            | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+#[test]
+fn test_sanitize_filename() {
+    let o = SanitizeOptions::default();
+
+    for (input, expected) in [
+        ("file.txt", "file.txt"),
+        ("", ""),
+        ("...", "_"),
+        (" a\tb\u{7}c ", "abc"),
+        ("a<b>c", "a_b_c"),
+        ("AUX", "AUX_"),
+        ("aux .tar.gz", "aux_ .tar.gz"),
+        ("lpt1.", "lpt1_"),
+        ("COMA.txt", "COMA.txt"),
+        ("con.txt", "con_.txt"),
+        ("COM¹.log", "COM¹_.log"),
+        ("conout$", "conout$_"),
+    ] {
+        let out = sanitize_filename(input, o);
+
+        assert_eq!(out, expected, "{input}");
+        assert!(validate_filename(&out).is_ok(), "{input}");
+    }
+
+    let o = SanitizeOptions {
+        max_bytes: 8,
+        replacement: None,
+        ..o
+    };
+
+    assert_eq!(sanitize_filename("a?bcdéfgh.txt", o), "abcd.txt");
+    assert_eq!(sanitize_filename("abcdéfgh", o), "abcdéfg");
+    assert_eq!(sanitize_filename("abcdefgé", o), "abcdefg");
+    assert_eq!(sanitize_filename("ab.verylongext", o), "ab.veryl");
+    assert_eq!(
+        sanitize_filename("CONSOLE.txt", SanitizeOptions { max_bytes: 7, ..o }),
+        "CO_.txt"
+    );
+    assert!(matches!(sanitize_filename("ok.txt", o), Cow::Borrowed(_)));
+
+    for (suffix, expected) in [
+        ("~", "con~.txt"),
+        ("?", "con_.txt"),
+        (":x ", "conx.txt"),
+        (" ", "con_.txt"),
+        (".", "con_.txt"),
+        ("a.b", "conab.txt"),
+    ] {
+        let o = SanitizeOptions {
+            reserved_suffix: suffix,
+            ..SanitizeOptions::default()
+        };
+        let out = sanitize_filename("con.txt", o);
+
+        assert_eq!(out, expected, "{suffix:?}");
+        assert!(validate_filename(&out).is_ok(), "{suffix:?}");
+    }
+
+    let o = SanitizeOptions { max_bytes: 0, ..o };
+
+    assert_eq!(sanitize_filename("abc.txt", o), "a");
+    assert_eq!(sanitize_filename("?", o), "_");
+    assert_eq!(sanitize_filename("", o), "");
+}
+
+#[test]
+fn test_reserved_names() {
+    for (input, expected) in [
+        ("con", Some("CON")),
+        ("CON", Some("CON")),
+        ("con.txt", Some("CON")),
+        ("NUL.tar.gz", Some("NUL")),
+        ("con .txt", Some("CON")),
+        ("aux  .  txt", Some("AUX")),
+        ("com1", Some("COM1")),
+        ("Com0.log", Some("COM0")),
+        ("COM¹", Some("COM¹")),
+        ("lpt².txt", Some("LPT²")),
+        ("LPT³", Some("LPT³")),
+        ("conin$", Some("CONIN$")),
+        ("CONOUT$.txt", Some("CONOUT$")),
+        ("prn.", Some("PRN")),
+        ("console", None),
+        ("con_", None),
+        ("acon", None),
+        ("com10", None),
+        ("com⁴", None),
+        ("conin", None),
+        ("lpt", None),
+        ("x.con", None),
+        (".con", None),
+        ("", None),
+    ] {
+        assert_eq!(reserved_name(input), expected, "{input}");
+
+        match expected {
+            Some(r) if !input.ends_with('.') => {
+                assert_eq!(
+                    validate_filename(input),
+                    Err(FsError::ReservedName(r)),
+                    "{input}"
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_validate_all() {
+    assert!(validate_filename_all(" a.txt ").is_empty());
+    assert_eq!(
+        validate_filename_all("a??|.txt"),
+        [FsError::InvalidChar('?'), FsError::InvalidChar('|')]
+    );
+    assert_eq!(
+        validate_filename_all(" nul "),
+        [FsError::ReservedName("NUL")]
+    );
+    assert!(validate_sub_path_all("a/b/c.txt").is_empty());
+    assert_eq!(
+        validate_sub_path_all("~/a./"),
+        [FsError::Home, FsError::EndsWithDot, FsError::EmptySegment]
+    );
+}
+
+#[test]
+fn test_validate_sub_path_hardened() {
+    use FsError::*;
+
+    for ok in [
+        "a/b.txt",
+        "dir\\file.tar.gz",
+        "100%/a",
+        "a%20b/c",
+        "caf%C3%A9.txt",
+        "ﬁle.txt",
+        "été/Ωmega",
+        "~a/b",
+    ] {
+        assert_eq!(validate_sub_path_hardened(ok), Ok(()), "{ok}");
+    }
+
+    for (input, expected) in [
+        ("../a", EndsWithDot),
+        ("%2e%2e/a", EncodedChar('.')),
+        ("%2E%2e%2Fa", EncodedChar('.')),
+        ("a%2fb", EncodedChar('/')),
+        ("a%5c..%5cb", EncodedChar('\\')),
+        ("%252e%252e/a", EncodedChar('%')),
+        ("a%00b", EncodedChar('\0')),
+        ("a%3a%24DATA", EncodedChar(':')),
+        ("..／a", NormalizedChar('／')),
+        ("a／b", NormalizedChar('／')),
+        ("a＼b", NormalizedChar('＼')),
+        ("．．/a", NormalizedChar('．')),
+        ("‥/a", NormalizedChar('‥')),
+        ("a/․", NormalizedChar('․')),
+        ("～/a", NormalizedChar('～')),
+        ("ａ：b", NormalizedChar('：')),
+        ("a\u{200B}/b", InvisibleChar('\u{200B}')),
+        (".\u{200D}./a", InvisibleChar('\u{200D}')),
+        ("a\u{FEFF}b", InvisibleChar('\u{FEFF}')),
+        ("a\u{202E}txt.exe", InvisibleChar('\u{202E}')),
+        ("\\\\?\\C:\\a", DevicePrefix),
+        ("\\\\.\\PhysicalDrive0", DevicePrefix),
+        ("//?/C:/a", DevicePrefix),
+        ("\\??\\C:\\a", DevicePrefix),
+        ("\\\\server\\share\\a", DevicePrefix),
+        ("%5c%5cserver/a", EncodedChar('\\')),
+        ("／／server/a", NormalizedChar('／')),
+        ("/a", Root),
+        ("c:/a", Root),
+        ("a/C:b", Root),
+        ("ab:c/d", AlternateDataStream),
+        ("a/con.txt", ReservedName("CON")),
+    ] {
+        assert_eq!(validate_sub_path_hardened(input), Err(expected), "{input}");
+    }
+
+    assert_eq!(
+        validate_sub_path_hardened_all("／%2e%2e\u{200B}"),
+        [
+            NormalizedChar('／'),
+            InvisibleChar('\u{200B}'),
+            EncodedChar('.'),
+            Root,
+            EmptySegment,
+            EndsWithDot
+        ]
+    );
+
+    // every spelling of "../" must be rejected, wherever it appears.
+    let dots = [
+        ".",
+        "%2e",
+        "%2E",
+        "%252e",
+        "．",
+        "․",
+        "\u{FE52}",
+        ".\u{200B}",
+    ];
+    let seps = ["/", "\\", "%2f", "%5C", "／", "＼", "\u{FE68}"];
+
+    for d1 in dots {
+        for d2 in dots {
+            for sep in seps {
+                for path in [
+                    format!("{d1}{d2}{sep}etc"),
+                    format!("a{sep}{d1}{d2}{sep}b"),
+                    format!("a{sep}{d1}{d2}"),
+                ] {
+                    assert!(validate_sub_path_hardened(&path).is_err(), "{path}");
+                }
+            }
+        }
+    }
+
+    // the normal validation ignores these tricks.
+    assert!(validate_sub_path("%2e%2e/a").is_ok());
+    assert!(validate_sub_path("..／a").is_ok());
+}
+
+#[test]
+fn test_validate_sub_path() {
+    assert!(validate_sub_path("test/~").is_ok());
+    assert!(validate_sub_path("test~/as.txt").is_ok());
+    assert!(validate_sub_path("~").is_ok());
+    assert!(validate_sub_path("~/").is_err());
+    assert!(validate_sub_path("~t~/as.txt").is_ok());
+    assert!(validate_sub_path("/").is_err());
+    assert!(validate_sub_path("txt.").is_err());
+    assert!(validate_sub_path("./").is_err());
+    assert!(validate_sub_path("../").is_err());
+    assert!(validate_sub_path("test/..txt").is_ok());
+    assert!(validate_sub_path("do_not_have_1_dots_in_path/./test.txt").is_err());
+    assert!(validate_sub_path("do_not_have_1_dots_in_path/./test.txt").is_err());
+    assert!(validate_sub_path("do_not_have_2_dots_in_path/../test.txt").is_err());
+    assert!(validate_sub_path("do_not_end_with_dot_in_path./text.txt").is_err());
+    assert!(validate_sub_path("path/do_not_end_with.").is_err());
+}
+
+//ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~:/?#[]@!$&'()*+,;=
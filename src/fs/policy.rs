@@ -0,0 +1,330 @@
+use super::{is_invalid_filename_char, reserved_name, INVALID_FILENAME_CHARS, RESERVED_NAMES};
+use crate::{
+    cmp::EqExt,
+    form_str::{Description, LenKind, Length},
+};
+use unicode_normalization::UnicodeNormalization;
+
+/// The names "." and ".." refer to the current and parent folders on every platform.
+const DOT_NAMES: [&str; 2] = [".", ".."];
+
+/// The printable ascii chars outside the portable filename character set, the other chars
+/// are rejected by the pattern of [FilenamePolicy::PortablePosix].
+const NON_PORTABLE_CHARS: [char; 30] = {
+    let mut out = ['\0'; 30];
+    let mut len = 0;
+    let mut b = b' ';
+
+    while b < 0x7F {
+        if !is_portable_char(b as char) {
+            out[len] = b as char;
+            len += 1;
+        }
+
+        b += 1;
+    }
+
+    assert!(len == out.len());
+    out
+};
+
+/// Returns true for the chars of the POSIX portable filename character set.
+const fn is_portable_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')
+}
+
+/// The rules a filename must follow, depending on the platform it is stored on.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::FilenamePolicy;
+///
+/// assert!(FilenamePolicy::Posix.validate("a:b").is_ok());
+/// assert!(FilenamePolicy::MacOs.validate("a:b").is_err());
+/// assert!(FilenamePolicy::Windows.names_collide("README", "readme"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum FilenamePolicy {
+    /// NTFS: no control chars nor `<>:"/\|?*`, no trailing dot, no device names such as
+    /// "CON", at most 255 UTF-16 units. A `:` after the name refers to an alternate data
+    /// stream. Names are case insensitive.
+    #[default]
+    Windows,
+    /// Linux and other Unix: only `/` and NUL are forbidden, "." and ".." are reserved,
+    /// at most 255 bytes. Names are case sensitive.
+    Posix,
+    /// APFS and HFS+: `/`, `:` and NUL are forbidden, "." and ".." are reserved, at most
+    /// 255 bytes. Names are case and normalization insensitive.
+    MacOs,
+    /// The POSIX portable filename character set: only ascii letters, digits, `.`, `_`
+    /// and `-`, not starting with `-`, at most 255 bytes. Names are case sensitive.
+    PortablePosix,
+    /// Every other policy at once, a name accepted here is accepted everywhere.
+    Strictest,
+}
+
+impl FilenamePolicy {
+    /// Describe the constraints of the policy, see [Format::describe](crate::form_str::Format::describe).
+    pub fn describe(self) -> Description {
+        let common = Description {
+            filename: true,
+            max_len: vec![self.max_len()],
+            reserved_names: self.reserved_names(),
+            trimmed: true,
+            ..Description::default()
+        };
+
+        match self {
+            Self::Windows => Description {
+                forbidden_chars: &INVALID_FILENAME_CHARS,
+                no_control_chars: true,
+                pattern: Some(r#"^[^<>:"/\\|?*\x00-\x1F\x7F-\x9F]*$"#),
+                ..common
+            },
+            Self::Posix => Description {
+                forbidden_chars: &['/', '\0'],
+                pattern: Some(r"^[^/\x00]*$"),
+                ..common
+            },
+            Self::MacOs => Description {
+                forbidden_chars: &['/', ':', '\0'],
+                pattern: Some(r"^[^/:\x00]*$"),
+                ..common
+            },
+            Self::PortablePosix | Self::Strictest => Description {
+                forbidden_chars: &NON_PORTABLE_CHARS,
+                no_control_chars: true,
+                pattern: Some(r"^[A-Za-z0-9._][A-Za-z0-9._-]*$"),
+                ..common
+            },
+        }
+    }
+
+    /// A `:` following the name refers to an NTFS alternate data stream.
+    pub(crate) fn has_data_streams(self) -> bool {
+        matches!(self, Self::Windows | Self::Strictest)
+    }
+
+    /// Returns true if two different spellings refer to the same file, such as "a.txt"
+    /// and "A.TXT" on Windows.
+    pub fn is_case_insensitive(self) -> bool {
+        matches!(self, Self::Windows | Self::MacOs | Self::Strictest)
+    }
+
+    /// Returns true if the char is not accepted in a filename.
+    pub fn is_invalid_char(self, c: char) -> bool {
+        match self {
+            Self::Windows => is_invalid_filename_char(c),
+            Self::Posix => matches!(c, '/' | '\0'),
+            Self::MacOs => matches!(c, '/' | ':' | '\0'),
+            Self::PortablePosix | Self::Strictest => !is_portable_char(c),
+        }
+    }
+
+    pub(crate) fn is_too_long(self, s: &str) -> bool {
+        let max = self.max_len();
+
+        match max.kind {
+            LenKind::Utf16 => s.encode_utf16().nth(max.len).is_some(),
+            _ => s.len() > max.len,
+        }
+    }
+
+    /// The maximum length of a filename.
+    pub fn max_len(self) -> Length {
+        match self {
+            Self::Windows => Length {
+                kind: LenKind::Utf16,
+                len: 255,
+            },
+            _ => Length {
+                kind: LenKind::Bytes,
+                len: 255,
+            },
+        }
+    }
+
+    /// Returns true if both names refer to the same file on this platform.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::fs::FilenamePolicy;
+    ///
+    /// // "é" precomposed and decomposed.
+    /// assert!(FilenamePolicy::MacOs.names_collide("caf\u{e9}", "CAFE\u{301}"));
+    /// assert!(!FilenamePolicy::Posix.names_collide("a.txt", "A.txt"));
+    /// ```
+    pub fn names_collide(self, a: &str, b: &str) -> bool {
+        match self {
+            Self::Posix | Self::PortablePosix => a == b,
+            Self::Windows => a.eq_ci(b),
+            Self::MacOs | Self::Strictest => a
+                .nfd()
+                .flat_map(char::to_lowercase)
+                .eq(b.nfd().flat_map(char::to_lowercase)),
+        }
+    }
+
+    pub(crate) fn rejects_leading_hyphen(self) -> bool {
+        matches!(self, Self::PortablePosix | Self::Strictest)
+    }
+
+    pub(crate) fn rejects_trailing_dot(self) -> bool {
+        matches!(self, Self::Windows | Self::Strictest)
+    }
+
+    /// Returns the reserved name matching the trimmed filename, if any.
+    pub fn reserved_name(self, s: &str) -> Option<&'static str> {
+        match self {
+            Self::Windows => reserved_name(s),
+            Self::Strictest => reserved_name(s).or_else(|| dot_name(s)),
+            _ => dot_name(s),
+        }
+    }
+
+    /// The names which are reserved.
+    pub fn reserved_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Windows | Self::Strictest => &RESERVED_NAMES,
+            _ => &DOT_NAMES,
+        }
+    }
+
+    /// Validate a filename, see [validate_filename_with](super::validate_filename_with).
+    #[inline]
+    pub fn validate(self, s: &str) -> super::Result<&str> {
+        super::validate_filename_with(s, self)
+    }
+
+    /// Validate a filename, see [validate_filename_all_with](super::validate_filename_all_with).
+    #[inline]
+    pub fn validate_all(self, s: &str) -> Vec<super::FsError> {
+        super::validate_filename_all_with(s, self)
+    }
+}
+
+fn dot_name(s: &str) -> Option<&'static str> {
+    DOT_NAMES.into_iter().find(|n| *n == s)
+}
+
+/// A [FilenamePolicy] known at compile time, used by
+/// [FilenameFor](crate::form_str::formats::FilenameFor).
+pub trait Policy {
+    const POLICY: FilenamePolicy;
+}
+
+/// Types selecting a [FilenamePolicy] at compile time.
+pub mod policies {
+    use super::{FilenamePolicy, Policy};
+
+    macro_rules! policy {
+        ($name:ident) => {
+            #[doc = concat!("Selects [FilenamePolicy::", stringify!($name), "].")]
+            #[derive(Clone, Copy, Debug, Default)]
+            pub struct $name;
+
+            impl Policy for $name {
+                const POLICY: FilenamePolicy = FilenamePolicy::$name;
+            }
+        };
+    }
+
+    policy!(MacOs);
+    policy!(PortablePosix);
+    policy!(Posix);
+    policy!(Strictest);
+    policy!(Windows);
+}
+
+#[test]
+fn test_policies() {
+    use super::FsError;
+    use FilenamePolicy::*;
+
+    assert_eq!(Windows.validate(" a.txt "), Ok("a.txt"));
+    assert_eq!(
+        Windows.validate("a.txt:b"),
+        Err(FsError::AlternateDataStream)
+    );
+    assert_eq!(Windows.validate(":b"), Err(FsError::InvalidChar(':')));
+    assert_eq!(Windows.validate(" :b"), Err(FsError::InvalidChar(':')));
+    assert_eq!(Windows.validate("c:"), Err(FsError::Root));
+    assert_eq!(Windows.validate(" C:b.txt"), Err(FsError::Root));
+    assert_eq!(Windows.validate("ab:c"), Err(FsError::AlternateDataStream));
+    assert_eq!(Posix.validate("c:"), Ok("c:"));
+    assert_eq!(MacOs.validate("c:"), Err(FsError::InvalidChar(':')));
+    assert_eq!(Windows.validate("a."), Err(FsError::EndsWithDot));
+    assert_eq!(Windows.validate("nul"), Err(FsError::ReservedName("NUL")));
+    assert_eq!(Windows.validate(&"é".repeat(255)), Ok(&*"é".repeat(255)));
+    assert_eq!(
+        Windows.validate(&"😀".repeat(128)),
+        Err(FsError::TooLong(255))
+    );
+
+    assert_eq!(Posix.validate("a:b*?."), Ok("a:b*?."));
+    assert_eq!(Posix.validate("con"), Ok("con"));
+    assert_eq!(Posix.validate("a/b"), Err(FsError::InvalidChar('/')));
+    assert_eq!(Posix.validate("a\0"), Err(FsError::InvalidChar('\0')));
+    assert_eq!(Posix.validate(".."), Err(FsError::ReservedName("..")));
+    assert_eq!(Posix.validate(&"é".repeat(128)), Err(FsError::TooLong(255)));
+
+    assert_eq!(MacOs.validate("a*b."), Ok("a*b."));
+    assert_eq!(MacOs.validate("a:b"), Err(FsError::InvalidChar(':')));
+    assert_eq!(MacOs.validate("."), Err(FsError::ReservedName(".")));
+
+    assert_eq!(PortablePosix.validate("a-b_c.1"), Ok("a-b_c.1"));
+    assert_eq!(
+        PortablePosix.validate("a b"),
+        Err(FsError::InvalidChar(' '))
+    );
+    assert_eq!(PortablePosix.validate("é"), Err(FsError::InvalidChar('é')));
+    assert_eq!(PortablePosix.validate("-a"), Err(FsError::StartsWithHyphen));
+
+    assert_eq!(Strictest.validate("a-b_c.1"), Ok("a-b_c.1"));
+    assert_eq!(
+        Strictest.validate_all("-a:b."),
+        [
+            FsError::AlternateDataStream,
+            FsError::EndsWithDot,
+            FsError::StartsWithHyphen
+        ]
+    );
+    assert_eq!(Strictest.validate("aux"), Err(FsError::ReservedName("AUX")));
+    assert_eq!(Strictest.validate(".."), Err(FsError::EndsWithDot));
+}
+
+#[test]
+fn test_describe() {
+    use FilenamePolicy::*;
+
+    for policy in [Windows, Posix, MacOs, PortablePosix, Strictest] {
+        let d = policy.describe();
+
+        for c in d.forbidden_chars {
+            assert!(policy.is_invalid_char(*c), "{policy:?} {c:?}");
+        }
+
+        for c in (' '..='~').filter(|c| policy.is_invalid_char(*c)) {
+            assert!(d.forbidden_chars.contains(&c), "{policy:?} {c:?}");
+        }
+    }
+
+    assert!(PortablePosix.describe().forbidden_chars.contains(&' '));
+    assert!(!PortablePosix.describe().forbidden_chars.contains(&'-'));
+}
+
+#[test]
+fn test_names_collide() {
+    use FilenamePolicy::*;
+
+    assert!(Windows.names_collide("Été.txt", "éTÉ.TXT"));
+    assert!(!Windows.names_collide("e\u{301}", "\u{e9}"));
+    assert!(!Posix.names_collide("a", "A"));
+    assert!(!PortablePosix.names_collide("a", "A"));
+    assert!(MacOs.names_collide("e\u{301}", "\u{c9}"));
+    assert!(Strictest.names_collide("e\u{301}", "\u{c9}"));
+
+    assert!(Windows.is_case_insensitive());
+    assert!(MacOs.is_case_insensitive());
+    assert!(!Posix.is_case_insensitive());
+}
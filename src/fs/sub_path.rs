@@ -0,0 +1,332 @@
+use super::{format_sub_path, Result};
+use std::{
+    borrow::Borrow,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A validated path relative to a folder, see [validate_sub_path](super::validate_sub_path).
+///
+/// Segments are trimmed and always separated by `/`. It is never empty, rooted, and never
+/// contains "." or ".." segments. For an owned version, see [SubPathBuf].
+#[derive(Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct SubPath(str);
+
+impl SubPath {
+    /// The string must be a valid sub path separated by `/`.
+    #[inline]
+    pub(crate) fn from_canonical(s: &str) -> &Self {
+        // SAFETY: `SubPath` is `#[repr(transparent)]` over `str`, both references have the
+        // same layout and the lifetime is kept.
+        unsafe { std::mem::transmute(s) }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The extension of the file name, without the dot. A name starting with a dot, such as
+    /// ".gitignore", has no extension.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::fs::SubPathBuf;
+    ///
+    /// assert_eq!(SubPathBuf::new("a/b.tar.gz").unwrap().extension(), Some("gz"));
+    /// assert_eq!(SubPathBuf::new("a/.gitignore").unwrap().extension(), None);
+    /// ```
+    pub fn extension(&self) -> Option<&str> {
+        match self.file_name().rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() => Some(ext),
+            _ => None,
+        }
+    }
+
+    /// The last segment.
+    pub fn file_name(&self) -> &str {
+        match self.0.rsplit_once('/') {
+            Some((_, name)) => name,
+            None => &self.0,
+        }
+    }
+
+    /// Appends a path to this one, the appended path is validated.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::fs::SubPathBuf;
+    ///
+    /// let dir = SubPathBuf::new("a").unwrap();
+    ///
+    /// assert_eq!(dir.join("b\\c.txt").unwrap().as_str(), "a/b/c.txt");
+    /// assert!(dir.join("../c.txt").is_err());
+    /// ```
+    pub fn join(&self, path: &str) -> Result<SubPathBuf> {
+        let path = format_sub_path(path)?;
+        let mut out = String::with_capacity(self.0.len() + 1 + path.len());
+
+        out.push_str(&self.0);
+        out.push('/');
+        out.push_str(&path);
+
+        Ok(SubPathBuf(out))
+    }
+
+    /// The path without its last segment, `None` if there is a single segment.
+    pub fn parent(&self) -> Option<&SubPath> {
+        self.0
+            .rsplit_once('/')
+            .map(|(parent, _)| Self::from_canonical(parent))
+    }
+
+    /// Resolve the path under a base folder. The result is always inside `base`, by
+    /// construction: every segment is validated to be a normal path component, it is not
+    /// empty, "." or "..", and it contains no separator nor `:`, so it cannot be a root, a
+    /// drive or a prefix replacing `base` when pushed. Like [Path::starts_with], this is a
+    /// lexical guarantee, the symbolic links under `base` are not resolved.
+    ///
+    /// # Example
+    /// ```
+    /// use std::path::Path;
+    /// use str_utils::fs::SubPathBuf;
+    ///
+    /// let path = SubPathBuf::new("a/b.txt").unwrap();
+    ///
+    /// assert_eq!(path.resolve_under(Path::new("/data")), Path::new("/data/a/b.txt"));
+    /// ```
+    pub fn resolve_under(&self, base: &Path) -> PathBuf {
+        let mut out = base.to_path_buf();
+        out.extend(self.segments());
+
+        // holds by construction, see above.
+        debug_assert!(out.starts_with(base));
+        out
+    }
+
+    /// Iterates over the segments, from the first folder to the file name.
+    pub fn segments(&self) -> std::str::Split<'_, char> {
+        self.0.split('/')
+    }
+}
+
+impl AsRef<Path> for SubPath {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.0)
+    }
+}
+
+impl AsRef<str> for SubPath {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for SubPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Deref for SubPath {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for SubPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl ToOwned for SubPath {
+    type Owned = SubPathBuf;
+
+    fn to_owned(&self) -> Self::Owned {
+        SubPathBuf(self.0.to_string())
+    }
+}
+
+/// An owned [SubPath].
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SubPathBuf(pub(super) String);
+
+impl SubPathBuf {
+    /// Validate and format a sub path, see [format_sub_path].
+    pub fn new(s: &str) -> Result<Self> {
+        format_sub_path(s).map(Self)
+    }
+
+    #[inline]
+    pub fn as_sub_path(&self) -> &SubPath {
+        SubPath::from_canonical(&self.0)
+    }
+
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Appends a path, see [SubPath::join]. The path is unchanged on error.
+    pub fn push(&mut self, path: &str) -> Result<()> {
+        let path = format_sub_path(path)?;
+
+        self.0.push('/');
+        self.0.push_str(&path);
+        Ok(())
+    }
+}
+
+impl AsRef<Path> for SubPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        Path::new(&self.0)
+    }
+}
+
+impl AsRef<str> for SubPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<SubPath> for SubPathBuf {
+    #[inline]
+    fn as_ref(&self) -> &SubPath {
+        self.as_sub_path()
+    }
+}
+
+impl Borrow<SubPath> for SubPathBuf {
+    #[inline]
+    fn borrow(&self) -> &SubPath {
+        self.as_sub_path()
+    }
+}
+
+impl Debug for SubPathBuf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Deref for SubPathBuf {
+    type Target = SubPath;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_sub_path()
+    }
+}
+
+impl Display for SubPathBuf {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<SubPathBuf> for PathBuf {
+    #[inline]
+    fn from(value: SubPathBuf) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<SubPathBuf> for String {
+    #[inline]
+    fn from(value: SubPathBuf) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for SubPathBuf {
+    type Err = super::FsError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for SubPathBuf {
+    type Error = super::FsError;
+
+    #[inline]
+    fn try_from(value: &str) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+#[test]
+fn test_sub_path() {
+    use super::FsError;
+
+    let p = SubPathBuf::new(" a / b\\c.tar.gz ").unwrap();
+
+    assert_eq!(p.as_str(), "a/b/c.tar.gz");
+    assert_eq!(p.segments().collect::<Vec<_>>(), ["a", "b", "c.tar.gz"]);
+    assert_eq!(p.file_name(), "c.tar.gz");
+    assert_eq!(p.extension(), Some("gz"));
+
+    let parent = p.parent().unwrap();
+
+    assert_eq!(parent.as_str(), "a/b");
+    assert_eq!(parent.extension(), None);
+    assert_eq!(parent.parent().unwrap().as_str(), "a");
+    assert_eq!(parent.parent().unwrap().parent(), None);
+
+    assert_eq!(parent.join("d").unwrap(), SubPathBuf::new("a/b/d").unwrap());
+    assert_eq!(parent.join("d/../e"), Err(FsError::EndsWithDot));
+    assert_eq!(parent.join("/e"), Err(FsError::Root));
+
+    let mut q = parent.to_owned();
+
+    assert!(q.push("x:y").is_err());
+    assert_eq!(q.as_str(), "a/b");
+    assert!(q.push("x").is_ok());
+    assert_eq!(q.as_str(), "a/b/x");
+
+    assert_eq!("..".parse::<SubPathBuf>(), Err(FsError::EndsWithDot));
+    assert_eq!(SubPathBuf::new("a//b"), Err(FsError::EmptySegment));
+
+    let base = Path::new("base");
+
+    assert_eq!(p.resolve_under(base), Path::new("base/a/b/c.tar.gz"));
+    assert!(p.resolve_under(base).starts_with(base));
+
+    // every segment is pushed as a normal component, whatever the platform.
+    for s in [
+        "a/~/b",
+        "c:/a",
+        "a/C:b",
+        "\\\\srv\\a",
+        "a/./b",
+        "a/ .. /b",
+        "a/...",
+        "/a",
+    ] {
+        let Ok(path) = SubPathBuf::new(s) else {
+            continue;
+        };
+
+        let resolved = path.resolve_under(base);
+        let rest = resolved.strip_prefix(base).unwrap();
+
+        assert!(
+            rest.components()
+                .all(|c| matches!(c, std::path::Component::Normal(_))),
+            "{s}"
+        );
+        assert_eq!(rest.components().count(), path.segments().count(), "{s}");
+    }
+}
@@ -0,0 +1,247 @@
+use super::{filename::stem_len, validate_filename, FsError, Result};
+use crate::{str_ci::StrCi, StrUtilsExt};
+use std::collections::HashSet;
+
+/// Options of [unique_filename].
+#[derive(Clone, Copy, Debug)]
+pub struct UniqueOptions {
+    /// Compare the names like [StrCi], like Windows and macOS. True by default.
+    pub case_insensitive: bool,
+    /// The maximum length in bytes, 255 by default.
+    pub max_bytes: usize,
+}
+
+impl Default for UniqueOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive: true,
+            max_bytes: 255,
+        }
+    }
+}
+
+/// Returns the first name among "Report.pdf", "Report (1).pdf", "Report (2).pdf", ... which
+/// is not in the existing names.
+///
+/// The name is validated with [validate_filename], the number is appended to the stem, the
+/// part before the first dot, so "a.tar.gz" becomes "a (1).tar.gz". The stem is truncated
+/// when the number does not fit in [UniqueOptions::max_bytes], a [FsError::TooLong] is
+/// returned when nothing of it is left.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::{unique_filename, UniqueOptions};
+///
+/// let existing = ["report.pdf", "Report (1).pdf"];
+///
+/// assert_eq!(
+///     unique_filename("Report.pdf", existing, UniqueOptions::default()).unwrap(),
+///     "Report (2).pdf"
+/// );
+/// ```
+pub fn unique_filename<I, S>(name: &str, existing: I, options: UniqueOptions) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let existing = existing.into_iter().collect::<Vec<_>>();
+    let existing = existing.iter().map(AsRef::as_ref);
+
+    if options.case_insensitive {
+        let existing = existing.map(StrCi::new).collect::<HashSet<_>>();
+
+        unique_filename_with(name, options.max_bytes, |candidate| {
+            existing.contains(StrCi::new(candidate))
+        })
+    } else {
+        let existing = existing.collect::<HashSet<_>>();
+
+        unique_filename_with(name, options.max_bytes, |candidate| {
+            existing.contains(candidate)
+        })
+    }
+}
+
+/// Returns the first name like [unique_filename] for which `exists` returns false, such as
+/// a check against a folder. `exists` must eventually return false.
+///
+/// # Example
+/// ```
+/// use str_utils::fs::unique_filename_with;
+///
+/// let name = unique_filename_with("a.txt", 255, |n| n == "a.txt").unwrap();
+///
+/// assert_eq!(name, "a (1).txt");
+/// ```
+pub fn unique_filename_with<F>(name: &str, max_bytes: usize, mut exists: F) -> Result<String>
+where
+    F: FnMut(&str) -> bool,
+{
+    let name = validate_filename(name)?;
+
+    if name.len() > max_bytes {
+        return Err(FsError::TooLong(max_bytes));
+    }
+
+    if !exists(name) {
+        return Ok(name.to_string());
+    }
+
+    let (stem, ext) = name.split_at(stem_len(name));
+
+    for n in 1.. {
+        let suffix = format!(" ({n})");
+
+        let Some(room) = max_bytes.checked_sub(suffix.len() + ext.len()) else {
+            return Err(FsError::TooLong(max_bytes));
+        };
+
+        let stem = stem.truncate_bytes(room).trim_end();
+
+        if stem.is_empty() {
+            return Err(FsError::TooLong(max_bytes));
+        }
+
+        let candidate = format!("{stem}{suffix}{ext}");
+
+        debug_assert!(validate_filename(&candidate).is_ok());
+
+        if !exists(&candidate) {
+            return Ok(candidate);
+        }
+    }
+
+    unreachable!()
+}
+
+#[test]
+fn test_unique_filename() {
+    let o = UniqueOptions::default();
+
+    assert_eq!(unique_filename("a.txt", [""; 0], o).unwrap(), "a.txt");
+    assert_eq!(unique_filename(" a.txt ", ["b.txt"], o).unwrap(), "a.txt");
+    assert_eq!(unique_filename("a.txt", ["A.TXT"], o).unwrap(), "a (1).txt");
+    assert_eq!(
+        unique_filename(
+            "a.txt",
+            ["A.TXT"],
+            UniqueOptions {
+                case_insensitive: false,
+                ..o
+            }
+        )
+        .unwrap(),
+        "a.txt"
+    );
+    assert_eq!(
+        unique_filename("a.tar.gz", ["a.tar.gz", "a (1).tar.gz"], o).unwrap(),
+        "a (2).tar.gz"
+    );
+    assert_eq!(unique_filename(".env", [".env"], o).unwrap(), ".env (1)");
+    assert_eq!(
+        unique_filename("README", ["readme"], o).unwrap(),
+        "README (1)"
+    );
+    assert_eq!(
+        unique_filename("a?.txt", [""; 0], o),
+        Err(FsError::InvalidChar('?'))
+    );
+    assert_eq!(
+        unique_filename("con.txt", [""; 0], o),
+        Err(FsError::ReservedName("CON"))
+    );
+
+    let o = UniqueOptions { max_bytes: 10, ..o };
+
+    assert_eq!(
+        unique_filename("abcdef.txt", ["abcdef.txt"], o).unwrap(),
+        "ab (1).txt"
+    );
+    assert_eq!(
+        unique_filename("aé.txt", ["aé.txt"], o).unwrap(),
+        "a (1).txt"
+    );
+    assert_eq!(
+        unique_filename("ab    .txt", ["ab    .txt"], o).unwrap(),
+        "ab (1).txt"
+    );
+    assert_eq!(
+        unique_filename("a.verylong", ["a.verylong"], o),
+        Err(FsError::TooLong(10))
+    );
+    assert_eq!(
+        unique_filename("abcdefghijk", [""; 0], o),
+        Err(FsError::TooLong(10))
+    );
+    assert_eq!(
+        unique_filename_with("abc.txt", 8, |_| true),
+        Err(FsError::TooLong(8))
+    );
+    assert_eq!(
+        unique_filename_with("é.txt", 9, |n| n == "é.txt"),
+        Err(FsError::TooLong(9))
+    );
+
+    let existing = (1..=9).map(|n| format!("ab ({n}).txt")).collect::<Vec<_>>();
+
+    assert_eq!(
+        unique_filename(
+            "abc.txt",
+            existing.iter().chain([&"abc.txt".to_string()]),
+            o
+        )
+        .unwrap(),
+        "a (10).txt"
+    );
+
+    let o = UniqueOptions::default();
+    let existing = (1..=1000)
+        .map(|n| format!("A ({n}).TXT"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        unique_filename("a.txt", existing.iter().chain([&"a.txt".into()]), o).unwrap(),
+        "a (1001).txt"
+    );
+    assert_eq!(
+        unique_filename(
+            "a.txt",
+            &existing,
+            UniqueOptions {
+                case_insensitive: false,
+                ..o
+            }
+        )
+        .unwrap(),
+        "a.txt"
+    );
+}
+
+#[test]
+fn test_unique_filename_in_dir() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().unwrap();
+
+    for _ in 0..3 {
+        let listing = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+
+        let name = unique_filename("Report.pdf", &listing, UniqueOptions::default()).unwrap();
+        let same = unique_filename_with("Report.pdf", 255, |n| dir.path().join(n).exists());
+
+        assert_eq!(same.unwrap(), name);
+        fs::write(dir.path().join(&name), b"").unwrap();
+    }
+
+    let mut listing = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+
+    listing.sort();
+
+    assert_eq!(listing, ["Report (1).pdf", "Report (2).pdf", "Report.pdf"]);
+}
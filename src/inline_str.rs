@@ -0,0 +1,113 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display, Formatter},
+    ops::Deref,
+};
+
+/// A string stored inline when it fits in `N` bytes, on the heap otherwise.
+///
+/// Cloning a short string only copies the inline buffer, without allocating.
+#[derive(Clone)]
+pub struct InlineStr<const N: usize = 22>(Repr<N>);
+
+#[derive(Clone)]
+enum Repr<const N: usize> {
+    Inline(u8, [u8; N]),
+    Heap(Box<str>),
+}
+
+impl<const N: usize> InlineStr<N> {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // SAFETY: the buffer is only filled by `From<&str>`, which copies the whole `&str`
+            // and stores its length, so `buf[..len]` is valid UTF-8.
+            Repr::Inline(len, buf) => unsafe {
+                std::str::from_utf8_unchecked(&buf[..*len as usize])
+            },
+            Repr::Heap(s) => s,
+        }
+    }
+
+    /// Returns true if the string is stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline(..))
+    }
+
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Repr::Inline(..) => self.as_str().to_owned(),
+            Repr::Heap(s) => s.into_string(),
+        }
+    }
+}
+
+impl<const N: usize> AsRef<str> for InlineStr<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Debug for InlineStr<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Deref for InlineStr<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Display for InlineStr<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> From<&str> for InlineStr<N> {
+    fn from(s: &str) -> Self {
+        if s.len() <= N && s.len() <= u8::MAX as usize {
+            let mut buf = [0; N];
+            buf[..s.len()].copy_from_slice(s.as_bytes());
+            Self(Repr::Inline(s.len() as u8, buf))
+        } else {
+            Self(Repr::Heap(s.into()))
+        }
+    }
+}
+
+impl<const N: usize> From<Cow<'_, str>> for InlineStr<N> {
+    fn from(s: Cow<'_, str>) -> Self {
+        match s {
+            Cow::Owned(s) if s.len() > N => Self(Repr::Heap(s.into_boxed_str())),
+            s => Self::from(&*s),
+        }
+    }
+}
+
+impl<const N: usize> From<String> for InlineStr<N> {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::from(Cow::Owned(s))
+    }
+}
+
+#[test]
+fn test_inline_str() {
+    let s = InlineStr::<4>::from("abcd");
+    assert!(s.is_inline());
+    assert_eq!(&*s, "abcd");
+
+    let s = InlineStr::<4>::from("abcde".to_string());
+    assert!(!s.is_inline());
+    assert_eq!(&*s, "abcde");
+
+    assert_eq!(InlineStr::<4>::from("é").into_string(), "é");
+}
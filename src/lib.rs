@@ -1,9 +1,13 @@
 mod char_ext;
 pub mod char_map;
 pub mod cmp;
+#[cfg(any(feature = "rusqlite", feature = "sqlx"))]
+mod db;
 pub mod form_str;
 pub mod fs;
+mod inline_str;
 mod none_if_empty;
+mod range_table;
 pub mod sanitizing;
 pub mod str_ci;
 mod str_utils_ext;
@@ -11,6 +15,7 @@ mod trim_in_place;
 pub mod url;
 
 pub use char_ext::*;
+pub use inline_str::InlineStr;
 pub use none_if_empty::NoneIfEmpty;
 pub use str_utils_ext::StrUtilsExt;
 pub use trim_in_place::TrimInPlace;
@@ -1,4 +1,4 @@
-use crate::form_str::FormStr;
+use crate::form_str::{FormStr, Storage};
 use std::{borrow::Cow, ops::Deref};
 
 pub trait NoneIfEmpty {
@@ -49,8 +49,8 @@ impl<'a> NoneIfEmpty for Cow<'a, str> {
     }
 }
 
-impl<F> NoneIfEmpty for FormStr<F> {
-    type Output = FormStr<F>;
+impl<F, S: Storage> NoneIfEmpty for FormStr<F, S> {
+    type Output = FormStr<F, S>;
 
     fn none_if_empty(self) -> Option<Self::Output> {
         if self.is_empty() {
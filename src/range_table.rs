@@ -0,0 +1,33 @@
+//! Lookup in the tables generated by build.rs with `write_range_table`.
+
+/// Returns the status and the string of a char in a table covering every code point.
+pub(crate) fn lookup(table: &'static [u8], c: char) -> (u8, &'static str) {
+    let u32_at =
+        |i: usize| u32::from_le_bytes([table[i], table[i + 1], table[i + 2], table[i + 3]]);
+    let count = u32_at(0) as usize;
+    let strings = &table[4 + count * 16..];
+
+    let (mut lo, mut hi) = (0, count);
+    let c = c as u32;
+
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let record = 4 + mid * 16;
+
+        if c < u32_at(record) {
+            hi = mid;
+        } else if c > u32_at(record + 4) {
+            lo = mid + 1;
+        } else {
+            let offset = u32_at(record + 8) as usize;
+            let len = table[record + 12] as usize;
+
+            // the strings are written from `String` slices on char boundaries.
+            let s = unsafe { std::str::from_utf8_unchecked(&strings[offset..offset + len]) };
+
+            return (table[record + 13], s);
+        }
+    }
+
+    unreachable!("the table covers every code point")
+}
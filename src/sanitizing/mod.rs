@@ -1,11 +1,26 @@
 use std::borrow::Cow;
 
+mod policy;
+mod spoofing;
+mod xml;
+
+pub use policy::{CharCategory, SanitizePolicy, SanitizePreset};
+pub use spoofing::{
+    detect_spoofing, eq_confusable, is_bidi_balanced, is_mixed_script, skeleton, SpoofIssue,
+};
+pub use xml::{
+    is_valid_xml10_char, is_valid_xml11_char, sanitize_xml10, sanitize_xml11, XmlContext,
+};
+
 pub fn is_valid_char(c: char) -> bool {
     (c as u32) >= 32 || ['\x07', '\x08', '\x0B', '\x0C', '\n', '\r', '\t'].contains(&c)
 }
 
+/// Remove the C0 controls other than BEL, BS, VT, FF, LF, CR and tab. For other chars such
+/// as DEL, the C1 controls or the bidi overrides, see [SanitizePolicy]. BEL, BS, VT and FF
+/// are not allowed in XML 1.0, see [sanitize_xml10].
 pub fn sanitize(s: &str) -> Cow<'_, str> {
-    if s.chars().all(|c| is_valid_char(c)) {
+    if s.chars().all(is_valid_char) {
         Cow::Borrowed(s)
     } else {
         Cow::Owned(s.chars().filter(|c| is_valid_char(*c)).collect())
@@ -0,0 +1,299 @@
+use std::borrow::Cow;
+
+/// The categories of chars a [SanitizePolicy] can allow or deny. Each char belongs to at
+/// most one category, chars outside every category are always kept.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(u16)]
+pub enum CharCategory {
+    /// `\t`.
+    Tab = 1 << 0,
+    /// `\n`, `\r` and the line and paragraph separators U+2028 and U+2029.
+    LineBreak = 1 << 1,
+    /// The other C0 controls U+0000 to U+001F, such as NUL, BEL, BS, VT, FF and ESC.
+    C0 = 1 << 2,
+    /// DEL U+007F.
+    Del = 1 << 3,
+    /// The C1 controls U+0080 to U+009F, such as NEL and CSI.
+    C1 = 1 << 4,
+    /// The bidi marks, embeddings, overrides and isolates, such as U+202E which reverses
+    /// the displayed text.
+    Bidi = 1 << 5,
+    /// The invisible chars U+200B, U+2060 and U+FEFF.
+    ZeroWidth = 1 << 6,
+    /// The zero width joiner U+200D and non-joiner U+200C, needed by some scripts and emoji
+    /// sequences.
+    Joiner = 1 << 7,
+    /// The 66 noncharacters, U+FDD0 to U+FDEF and the last two code points of each plane.
+    Noncharacter = 1 << 8,
+    /// The private use areas, U+E000 to U+F8FF and the planes 15 and 16.
+    PrivateUse = 1 << 9,
+    /// The replacement char U+FFFD, which lone surrogates become when decoded lossily since
+    /// a `str` cannot contain them.
+    Replacement = 1 << 10,
+}
+
+impl CharCategory {
+    /// Every category.
+    pub const ALL: [Self; 11] = [
+        Self::Tab,
+        Self::LineBreak,
+        Self::C0,
+        Self::Del,
+        Self::C1,
+        Self::Bidi,
+        Self::ZeroWidth,
+        Self::Joiner,
+        Self::Noncharacter,
+        Self::PrivateUse,
+        Self::Replacement,
+    ];
+
+    /// The category of a char, `None` for the other chars.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::sanitizing::CharCategory;
+    ///
+    /// assert_eq!(CharCategory::of('\u{202E}'), Some(CharCategory::Bidi));
+    /// assert_eq!(CharCategory::of('a'), None);
+    /// ```
+    pub const fn of(c: char) -> Option<Self> {
+        Some(match c {
+            '\t' => Self::Tab,
+            '\n' | '\r' | '\u{2028}' | '\u{2029}' => Self::LineBreak,
+            '\0'..='\x1F' => Self::C0,
+            '\x7F' => Self::Del,
+            '\u{80}'..='\u{9F}' => Self::C1,
+            '\u{61C}'
+            | '\u{200E}'
+            | '\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2066}'..='\u{2069}' => Self::Bidi,
+            '\u{200B}' | '\u{2060}' | '\u{FEFF}' => Self::ZeroWidth,
+            '\u{200C}' | '\u{200D}' => Self::Joiner,
+            '\u{FDD0}'..='\u{FDEF}' => Self::Noncharacter,
+            _ if c as u32 & 0xFFFE == 0xFFFE => Self::Noncharacter,
+            '\u{E000}'..='\u{F8FF}' | '\u{F0000}'..='\u{10FFFF}' => Self::PrivateUse,
+            '\u{FFFD}' => Self::Replacement,
+            _ => return None,
+        })
+    }
+}
+
+/// Ready-made [SanitizePolicy] values.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SanitizePreset {
+    /// Removes the chars forbidden or discouraged by XML 1.0: the C0 controls except tab and
+    /// line breaks, DEL, the C1 controls and the noncharacters.
+    XmlSafe,
+    /// Keeps a log record on one line and free of terminal escapes: replaces the line
+    /// breaks, the controls, the bidi chars and the noncharacters by U+FFFD.
+    JsonLogSafe,
+    /// Replaces the line breaks and the controls other than tab by a space.
+    SingleLine,
+    /// Removes what renders differently than it reads: the controls other than tab and line
+    /// breaks, the bidi chars, the zero width chars, the noncharacters and the private use
+    /// chars.
+    DisplaySafe,
+}
+
+/// The chars to remove or replace from a string, built from the [CharCategory] to deny.
+/// Nothing is denied by default.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::{CharCategory, SanitizePolicy, SanitizePreset};
+///
+/// let policy = SanitizePolicy::new()
+///     .deny(CharCategory::C0)
+///     .deny(CharCategory::Bidi)
+///     .replace_with('?');
+///
+/// assert_eq!(policy.sanitize("a\u{1B}b\u{202E}c"), "a?b?c");
+///
+/// let policy = SanitizePolicy::from(SanitizePreset::SingleLine).allow(CharCategory::C0);
+///
+/// assert_eq!(policy.sanitize("a\r\nb\x07"), "a  b\x07");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SanitizePolicy {
+    denied: u16,
+    replacement: Option<char>,
+}
+
+impl SanitizePolicy {
+    /// A policy which keeps every char.
+    pub const fn new() -> Self {
+        Self {
+            denied: 0,
+            replacement: None,
+        }
+    }
+
+    pub const fn preset(preset: SanitizePreset) -> Self {
+        use CharCategory::*;
+
+        let controls = Self::new().deny(C0).deny(Del).deny(C1);
+
+        match preset {
+            SanitizePreset::XmlSafe => controls.deny(Noncharacter),
+            SanitizePreset::JsonLogSafe => controls
+                .deny(LineBreak)
+                .deny(Bidi)
+                .deny(Noncharacter)
+                .replace_with('\u{FFFD}'),
+            SanitizePreset::SingleLine => controls.deny(LineBreak).replace_with(' '),
+            SanitizePreset::DisplaySafe => controls
+                .deny(Bidi)
+                .deny(ZeroWidth)
+                .deny(Noncharacter)
+                .deny(PrivateUse),
+        }
+    }
+
+    /// Keep the chars of a category.
+    pub const fn allow(mut self, category: CharCategory) -> Self {
+        self.denied &= !(category as u16);
+        self
+    }
+
+    /// Remove or replace the chars of a category.
+    pub const fn deny(mut self, category: CharCategory) -> Self {
+        self.denied |= category as u16;
+        self
+    }
+
+    /// Remove the denied chars, the default.
+    pub const fn remove(mut self) -> Self {
+        self.replacement = None;
+        self
+    }
+
+    /// Replace each denied char by a char. The replacement is removed when it is denied too.
+    pub const fn replace_with(mut self, c: char) -> Self {
+        self.replacement = Some(c);
+        self
+    }
+
+    pub const fn is_allowed(&self, c: char) -> bool {
+        match CharCategory::of(c) {
+            Some(category) => self.denied & category as u16 == 0,
+            None => true,
+        }
+    }
+
+    pub const fn is_denied(&self, category: CharCategory) -> bool {
+        self.denied & category as u16 != 0
+    }
+
+    pub const fn replacement(&self) -> Option<char> {
+        self.replacement
+    }
+
+    /// Remove or replace the denied chars, the string is borrowed when every char is
+    /// allowed.
+    pub fn sanitize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let Some(idx) = s.find(|c| !self.is_allowed(c)) else {
+            return Cow::Borrowed(s);
+        };
+
+        let replacement = self.replacement.filter(|c| self.is_allowed(*c));
+        let mut out = String::with_capacity(s.len());
+
+        out.push_str(&s[..idx]);
+
+        for c in s[idx..].chars() {
+            if self.is_allowed(c) {
+                out.push(c);
+            } else {
+                out.extend(replacement);
+            }
+        }
+
+        Cow::Owned(out)
+    }
+}
+
+impl From<SanitizePreset> for SanitizePolicy {
+    #[inline]
+    fn from(value: SanitizePreset) -> Self {
+        Self::preset(value)
+    }
+}
+
+#[test]
+fn test_char_category() {
+    for (c, category) in [
+        ('\t', Some(CharCategory::Tab)),
+        ('\r', Some(CharCategory::LineBreak)),
+        ('\u{2029}', Some(CharCategory::LineBreak)),
+        ('\0', Some(CharCategory::C0)),
+        ('\x0B', Some(CharCategory::C0)),
+        ('\x7F', Some(CharCategory::Del)),
+        ('\u{85}', Some(CharCategory::C1)),
+        ('\u{2067}', Some(CharCategory::Bidi)),
+        ('\u{FEFF}', Some(CharCategory::ZeroWidth)),
+        ('\u{200D}', Some(CharCategory::Joiner)),
+        ('\u{FDD0}', Some(CharCategory::Noncharacter)),
+        ('\u{FFFE}', Some(CharCategory::Noncharacter)),
+        ('\u{10FFFF}', Some(CharCategory::Noncharacter)),
+        ('\u{E000}', Some(CharCategory::PrivateUse)),
+        ('\u{10FFFD}', Some(CharCategory::PrivateUse)),
+        ('\u{FFFD}', Some(CharCategory::Replacement)),
+        (' ', None),
+        ('é', None),
+        ('\u{FFFC}', None),
+    ] {
+        assert_eq!(CharCategory::of(c), category, "{c:?}");
+    }
+}
+
+#[test]
+fn test_sanitize_policy() {
+    let input = "a\tb\r\nc\x07\x7F\u{9B}d\u{202E}e\u{200B}f\u{200D}g\u{FFFF}h\u{E000}i\u{FFFD}";
+
+    for (preset, expected) in [
+        (
+            SanitizePreset::XmlSafe,
+            "a\tb\r\ncd\u{202E}e\u{200B}f\u{200D}gh\u{E000}i\u{FFFD}",
+        ),
+        (
+            SanitizePreset::JsonLogSafe,
+            "a\tb\u{FFFD}\u{FFFD}c\u{FFFD}\u{FFFD}\u{FFFD}d\u{FFFD}e\u{200B}f\u{200D}g\u{FFFD}h\u{E000}i\u{FFFD}",
+        ),
+        (
+            SanitizePreset::SingleLine,
+            "a\tb  c   d\u{202E}e\u{200B}f\u{200D}g\u{FFFF}h\u{E000}i\u{FFFD}",
+        ),
+        (
+            SanitizePreset::DisplaySafe,
+            "a\tb\r\ncdef\u{200D}ghi\u{FFFD}",
+        ),
+    ] {
+        assert_eq!(
+            SanitizePolicy::from(preset).sanitize(input),
+            expected,
+            "{preset:?}"
+        );
+    }
+
+    let policy = SanitizePolicy::new().deny(CharCategory::Replacement);
+
+    assert!(matches!(policy.sanitize("abc"), Cow::Borrowed("abc")));
+    assert_eq!(policy.sanitize("a\u{FFFD}b"), "ab");
+    assert_eq!(policy.replace_with('\u{FFFD}').sanitize("a\u{FFFD}b"), "ab");
+    assert_eq!(policy.replace_with('_').sanitize("a\u{FFFD}b"), "a_b");
+    assert_eq!(
+        policy.replace_with('_').remove().sanitize("a\u{FFFD}b"),
+        "ab"
+    );
+    assert!(policy.is_denied(CharCategory::Replacement));
+    assert!(!policy
+        .allow(CharCategory::Replacement)
+        .is_denied(CharCategory::Replacement));
+    assert_eq!(SanitizePolicy::default(), SanitizePolicy::new());
+    assert_eq!(
+        SanitizePolicy::new().sanitize("\0\u{202E}"),
+        Cow::Borrowed("\0\u{202E}")
+    );
+}
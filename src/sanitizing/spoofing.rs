@@ -0,0 +1,315 @@
+use super::CharCategory;
+use crate::{fs::is_invisible_char, range_table};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The scripts which are commonly written together in a single word: Japanese, Korean and
+/// Chinese with Bopomofo.
+const SCRIPT_SETS: [&[&str]; 3] = [
+    &["Hani", "Hira", "Kana"],
+    &["Hani", "Hang"],
+    &["Hani", "Bopo"],
+];
+
+/// A spoofing attempt reported by [detect_spoofing].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SpoofIssue {
+    /// A bidi embedding, override or isolate not closed on its line, which reorders the
+    /// text following it, such as in CVE-2021-42574. Contains its byte index.
+    UnbalancedBidi(usize),
+    /// A char which is not rendered, such as a zero width space. Contains its byte index.
+    InvisibleChar(usize, char),
+    /// A word mixing scripts, such as "pаypal" with a Cyrillic "а". Contains the byte range
+    /// of the word.
+    MixedScript(usize, usize),
+}
+
+/// Returns the spoofing attempts found in a string, in the order of the text.
+///
+/// The bidi marks such as U+200F are not reported as invisible chars since they are needed
+/// by right-to-left text, nor the joiners, the variation selectors and the emoji tags
+/// following a visible char, such as in "❤\u{FE0F}" or the ZWJ sequence of a family emoji.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::{detect_spoofing, SpoofIssue};
+///
+/// assert_eq!(detect_spoofing("paypal"), []);
+/// assert_eq!(detect_spoofing("p\u{430}ypal"), [SpoofIssue::MixedScript(0, 7)]);
+/// assert_eq!(
+///     detect_spoofing("admin\u{202E}txt.exe"),
+///     [SpoofIssue::UnbalancedBidi(5)]
+/// );
+/// assert_eq!(
+///     detect_spoofing("ad\u{200B}min"),
+///     [SpoofIssue::InvisibleChar(2, '\u{200B}')]
+/// );
+/// ```
+pub fn detect_spoofing(s: &str) -> Vec<SpoofIssue> {
+    let mut issues = unbalanced_bidi(s)
+        .into_iter()
+        .map(SpoofIssue::UnbalancedBidi)
+        .collect::<Vec<_>>();
+
+    let mut after_base = false;
+
+    for (i, c) in s.char_indices() {
+        let invisible = is_invisible_char(c);
+        let modifier = is_modifier(c);
+
+        if invisible && CharCategory::of(c) != Some(CharCategory::Bidi) && !(modifier && after_base)
+        {
+            issues.push(SpoofIssue::InvisibleChar(i, c));
+        }
+
+        after_base = match invisible {
+            true => after_base && modifier,
+            false => !c.is_whitespace() && !c.is_control(),
+        };
+    }
+
+    issues.extend(
+        s.split_word_bound_indices()
+            .filter(|(_, word)| is_mixed_script(word))
+            .map(|(i, word)| SpoofIssue::MixedScript(i, i + word.len())),
+    );
+
+    issues.sort_by_key(|issue| match *issue {
+        SpoofIssue::UnbalancedBidi(i)
+        | SpoofIssue::InvisibleChar(i, _)
+        | SpoofIssue::MixedScript(i, _) => i,
+    });
+
+    issues
+}
+
+/// Returns true for the invisible chars which modify the char before them: the joiners, the
+/// variation selectors and the tags of the emoji flags such as "🏴\u{E0067}\u{E0062}...".
+fn is_modifier(c: char) -> bool {
+    CharCategory::of(c) == Some(CharCategory::Joiner)
+        || matches!(
+            c,
+            '\u{180B}'..='\u{180D}'
+                | '\u{180F}'
+                | '\u{FE00}'..='\u{FE0F}'
+                | '\u{E0020}'..='\u{E007F}'
+                | '\u{E0100}'..='\u{E01EF}'
+        )
+}
+
+/// Returns true when every bidi embedding, override and isolate is closed on its line.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::is_bidi_balanced;
+///
+/// assert!(is_bidi_balanced("\u{2067}שלום\u{2069} world"));
+/// assert!(!is_bidi_balanced("/* \u{202E} } \u{2066}if (isAdmin)\u{2069} \u{2066} */"));
+/// ```
+pub fn is_bidi_balanced(s: &str) -> bool {
+    unbalanced_bidi(s).is_empty()
+}
+
+/// The byte index of the first bidi control left open on each line.
+///
+/// A PDF closes the last embedding or override unless an isolate was opened after it, and a
+/// PDI closes the last isolate with everything opened after it, like the bidi algorithm of
+/// UAX #9. A closing char without opening has no effect.
+fn unbalanced_bidi(s: &str) -> Vec<usize> {
+    let mut open = Vec::new();
+    let mut unbalanced = Vec::new();
+
+    for (i, c) in s.char_indices() {
+        match c {
+            // LRE, RLE, LRO and RLO.
+            '\u{202A}'..='\u{202B}' | '\u{202D}'..='\u{202E}' => open.push((i, false)),
+            // LRI, RLI and FSI.
+            '\u{2066}'..='\u{2068}' => open.push((i, true)),
+            // PDF.
+            '\u{202C}' if open.last().is_some_and(|(_, isolate)| !isolate) => {
+                open.pop();
+            }
+            // PDI.
+            '\u{2069}' => {
+                if let Some(idx) = open.iter().rposition(|(_, isolate)| *isolate) {
+                    open.truncate(idx);
+                }
+            }
+            // the paragraph separators of UAX #9 and the line separator end a line.
+            '\n' | '\r' | '\x1C'..='\x1E' | '\u{85}' | '\u{2028}' | '\u{2029}' => {
+                unbalanced.extend(open.first().map(|(i, _)| *i));
+                open.clear();
+            }
+            _ => {}
+        }
+    }
+
+    unbalanced.extend(open.first().map(|(i, _)| *i));
+    unbalanced
+}
+
+/// Returns true when a word contains letters of many scripts, such as "pаypal" with a
+/// Cyrillic "а". The chars common to every script, such as digits and punctuation, are
+/// ignored, and Han may be mixed with Hiragana and Katakana, Hangul or Bopomofo.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::is_mixed_script;
+///
+/// assert!(!is_mixed_script("paypal2"));
+/// assert!(!is_mixed_script("東京タワー"));
+/// assert!(is_mixed_script("p\u{430}ypal"));
+/// ```
+pub fn is_mixed_script(word: &str) -> bool {
+    static TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/script.bin"));
+
+    let mut scripts = Vec::<&str>::new();
+
+    for c in word.chars() {
+        if let (1, script) = range_table::lookup(TABLE, c) {
+            if !scripts.contains(&script) {
+                scripts.push(script);
+            }
+        }
+    }
+
+    scripts.len() > 1
+        && !SCRIPT_SETS
+            .iter()
+            .any(|set| scripts.iter().all(|s| set.contains(s)))
+}
+
+/// The skeleton of a string following UTS #39, shared by the strings which look the same,
+/// such as "paypal" with a Latin or a Cyrillic "a", or "rn" and "m".
+///
+/// The skeleton is only meant to be compared, it is not a readable string and it is case
+/// sensitive.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::skeleton;
+///
+/// assert_eq!(skeleton("p\u{430}ypal"), skeleton("paypal"));
+/// ```
+pub fn skeleton(s: &str) -> String {
+    static TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/confusable.bin"));
+
+    let mut mapped = String::with_capacity(s.len());
+
+    for c in s.nfd() {
+        match range_table::lookup(TABLE, c) {
+            (1, prototype) => mapped.push_str(prototype),
+            _ => mapped.push(c),
+        }
+    }
+
+    mapped.nfd().collect()
+}
+
+/// Returns true when two strings look the same, they have the same [skeleton].
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::eq_confusable;
+///
+/// assert!(eq_confusable("paypal", "p\u{430}ypal"));
+/// assert!(!eq_confusable("paypal", "paypai"));
+/// ```
+pub fn eq_confusable(a: &str, b: &str) -> bool {
+    a == b || skeleton(a) == skeleton(b)
+}
+
+#[test]
+fn test_unbalanced_bidi() {
+    for (input, expected) in [
+        ("abc", &[][..]),
+        ("a\u{202E}b\u{202C}c", &[]),
+        ("a\u{2067}b\u{202E}c\u{2069}d", &[]),
+        ("a\u{202C}\u{2069}b", &[]),
+        ("a\u{200F}b", &[]),
+        ("a\u{202E}b", &[1]),
+        ("a\u{2066}b\u{202C}c", &[1]),
+        ("a\u{202E}b\nc\u{202C}", &[1]),
+        ("a\u{202E}\u{2066}\u{202C}\u{2069}", &[1]),
+        ("a\u{2067}b\n\u{202D}c\u{2029}d", &[1, 6]),
+        (
+            "/*\u{202E} } \u{2066}if (isAdmin)\u{2069} \u{2066} begin admins only */",
+            &[2],
+        ),
+    ] {
+        assert_eq!(unbalanced_bidi(input), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn test_is_mixed_script() {
+    for word in [
+        "",
+        "paypal",
+        "PayPal2",
+        "café",
+        "Москва",
+        "東京",
+        "ひらがなと漢字",
+        "한국語",
+        "a1-b",
+    ] {
+        assert!(!is_mixed_script(word), "{word}");
+    }
+
+    for word in ["p\u{430}ypal", "\u{3A1}aypal", "abcא", "東京a"] {
+        assert!(is_mixed_script(word), "{word}");
+    }
+}
+
+#[test]
+fn test_eq_confusable() {
+    for (a, b) in [
+        ("paypal", "p\u{430}ypal"),
+        ("paypal", "p\u{430}yp\u{430}l"),
+        ("rn", "m"),
+        ("l", "1"),
+        ("O", "0"),
+        ("\u{e9}", "e\u{301}"),
+        ("scope", "\u{455}\u{441}\u{43E}\u{440}\u{435}"),
+    ] {
+        assert!(eq_confusable(a, b), "{a} {b}");
+    }
+
+    for (a, b) in [("paypal", "PayPal"), ("paypal", "paypa"), ("a", "b")] {
+        assert!(!eq_confusable(a, b), "{a} {b}");
+    }
+}
+
+#[test]
+fn test_detect_spoofing() {
+    assert_eq!(detect_spoofing(""), []);
+    assert_eq!(detect_spoofing("Jean Tremblay"), []);
+    assert_eq!(detect_spoofing("\u{5D0}\u{200F} abc"), []);
+
+    for emoji in [
+        "I \u{2764}\u{FE0F} you",
+        "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+        "\u{2764}\u{FE0F}\u{200D}\u{1F525}",
+        "\u{1F3F4}\u{E0067}\u{E0062}\u{E0065}\u{E006E}\u{E0067}\u{E007F}",
+        "\u{645}\u{6CC}\u{200C}\u{62E}\u{648}\u{627}\u{647}\u{645}",
+    ] {
+        assert_eq!(detect_spoofing(emoji), [], "{emoji:?}");
+    }
+
+    assert_eq!(
+        detect_spoofing("\u{FE0F}a \u{200D}b"),
+        [
+            SpoofIssue::InvisibleChar(0, '\u{FE0F}'),
+            SpoofIssue::InvisibleChar(5, '\u{200D}'),
+        ]
+    );
+    assert_eq!(
+        detect_spoofing("p\u{430}ypal\u{200B} \u{202E}x"),
+        [
+            SpoofIssue::MixedScript(0, 7),
+            SpoofIssue::InvisibleChar(7, '\u{200B}'),
+            SpoofIssue::UnbalancedBidi(11),
+        ]
+    );
+}
@@ -0,0 +1,259 @@
+use std::{borrow::Cow, fmt::Write};
+
+/// Where a string sanitized by [sanitize_xml10] or [sanitize_xml11] is written.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum XmlContext {
+    /// Markup or text which is already escaped: only the invalid chars and the char
+    /// references to invalid chars, such as "&#xD800;" or "&#xFFFF;", are removed.
+    Raw,
+    /// Text content: `&`, `<` and `>` are escaped, as well as `\r` into "&#13;", which a
+    /// parser would otherwise normalize into a line feed.
+    #[default]
+    Text,
+    /// An attribute value: `&`, `<`, `>`, `"` and `'` are escaped, as well as tab and line
+    /// breaks, which a parser would otherwise normalize into spaces.
+    Attribute,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Version {
+    Xml10,
+    Xml11,
+}
+
+/// Returns true for the chars allowed in an XML 1.0 document: tab, line breaks, and every
+/// char from U+0020 except the surrogates, U+FFFE and U+FFFF.
+///
+/// Unlike [is_valid_char](super::is_valid_char), BEL, BS, VT and FF are not allowed.
+pub fn is_valid_xml10_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | ' '..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Returns true for the chars allowed in an XML 1.1 document: every char except NUL, the
+/// surrogates, U+FFFE and U+FFFF.
+///
+/// The other C0 controls, DEL and the C1 controls except NEL are restricted, they must be
+/// written as char references such as "&#x1;".
+pub fn is_valid_xml11_char(c: char) -> bool {
+    matches!(c, '\u{1}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// The XML 1.1 chars which must be written as char references.
+fn is_restricted_xml11_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1}'..='\u{8}'
+            | '\u{B}'..='\u{C}'
+            | '\u{E}'..='\u{1F}'
+            | '\u{7F}'..='\u{84}'
+            | '\u{86}'..='\u{9F}'
+    )
+}
+
+/// Repair a string for an XML 1.0 document: the invalid chars are removed and the string is
+/// escaped for its context. The string is borrowed when nothing changes.
+///
+/// A `str` cannot contain a surrogate, but an already escaped string can contain a char
+/// reference to one, which is removed in the [XmlContext::Raw] context.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::{sanitize_xml10, XmlContext};
+///
+/// assert_eq!(sanitize_xml10("a\x07 < b & c", XmlContext::Text), "a &lt; b &amp; c");
+/// assert_eq!(sanitize_xml10("\"a\"\n", XmlContext::Attribute), "&quot;a&quot;&#10;");
+/// assert_eq!(sanitize_xml10("<a>&#xD800;&amp;</a>", XmlContext::Raw), "<a>&amp;</a>");
+/// ```
+pub fn sanitize_xml10(s: &str, context: XmlContext) -> Cow<'_, str> {
+    sanitize_xml(s, context, Version::Xml10)
+}
+
+/// Repair a string for an XML 1.1 document like [sanitize_xml10]. NUL, U+FFFE and U+FFFF are
+/// removed and the restricted chars, such as BEL, are written as char references.
+///
+/// # Example
+/// ```
+/// use str_utils::sanitizing::{sanitize_xml11, XmlContext};
+///
+/// assert_eq!(sanitize_xml11("a\x07\0b", XmlContext::Text), "a&#x7;b");
+/// ```
+pub fn sanitize_xml11(s: &str, context: XmlContext) -> Cow<'_, str> {
+    sanitize_xml(s, context, Version::Xml11)
+}
+
+fn sanitize_xml(s: &str, context: XmlContext, version: Version) -> Cow<'_, str> {
+    let is_valid = |c: char| match version {
+        Version::Xml10 => is_valid_xml10_char(c),
+        Version::Xml11 => is_valid_xml11_char(c) && !is_restricted_xml11_char(c),
+    };
+
+    let needs_change = |c: char| !is_valid(c) || escape(c, context).is_some();
+
+    // in the raw context, `&` only changes when it starts a reference to an invalid char.
+    let Some(idx) = s.find(|c| needs_change(c) || (c == '&' && context == XmlContext::Raw)) else {
+        return Cow::Borrowed(s);
+    };
+
+    let mut out = String::with_capacity(s.len() + 16);
+    let mut rest = &s[idx..];
+
+    out.push_str(&s[..idx]);
+
+    while let Some(c) = rest.chars().next() {
+        if c == '&' && context == XmlContext::Raw {
+            if let Some((referenced, len)) = char_reference(rest) {
+                let valid = match version {
+                    Version::Xml10 => referenced.is_some_and(is_valid_xml10_char),
+                    Version::Xml11 => referenced.is_some_and(is_valid_xml11_char),
+                };
+
+                if valid {
+                    out.push_str(&rest[..len]);
+                }
+
+                rest = &rest[len..];
+                continue;
+            }
+        }
+
+        if let Some(e) = escape(c, context) {
+            out.push_str(e);
+        } else if is_valid(c) {
+            out.push(c);
+        } else if version == Version::Xml11 && is_restricted_xml11_char(c) {
+            let _ = write!(out, "&#x{:X};", c as u32);
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if out == s {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(out)
+    }
+}
+
+fn escape(c: char, context: XmlContext) -> Option<&'static str> {
+    match (c, context) {
+        (_, XmlContext::Raw) => None,
+        ('&', _) => Some("&amp;"),
+        ('<', _) => Some("&lt;"),
+        // escaped for the "]]>" sequence, which is not allowed in text.
+        ('>', _) => Some("&gt;"),
+        ('"', XmlContext::Attribute) => Some("&quot;"),
+        ('\'', XmlContext::Attribute) => Some("&apos;"),
+        ('\t', XmlContext::Attribute) => Some("&#9;"),
+        ('\n', XmlContext::Attribute) => Some("&#10;"),
+        ('\r', _) => Some("&#13;"),
+        _ => None,
+    }
+}
+
+/// Parse a char reference such as "&#233;" or "&#xE9;" at the start of a string. Returns the
+/// referenced char, `None` for a surrogate or an overflow, and the length of the reference.
+fn char_reference(s: &str) -> Option<(Option<char>, usize)> {
+    let (digits, radix, prefix) = match s.strip_prefix("&#x") {
+        Some(r) => (r, 16, 3),
+        None => (s.strip_prefix("&#")?, 10, 2),
+    };
+
+    let end = digits.find(';')?;
+    let digits = &digits[..end];
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+
+    let c = u32::from_str_radix(digits, radix)
+        .ok()
+        .and_then(char::from_u32);
+
+    Some((c, prefix + end + 1))
+}
+
+#[test]
+fn test_is_valid_xml_char() {
+    for c in [
+        '\t',
+        '\n',
+        '\r',
+        ' ',
+        'a',
+        '\u{85}',
+        '\u{D7FF}',
+        '\u{E000}',
+        '\u{FFFD}',
+        '\u{10000}',
+        '\u{10FFFF}',
+    ] {
+        assert!(is_valid_xml10_char(c), "{c:?}");
+        assert!(is_valid_xml11_char(c), "{c:?}");
+    }
+
+    for c in [
+        '\0', '\x07', '\x08', '\x0B', '\x0C', '\x1F', '\u{FFFE}', '\u{FFFF}',
+    ] {
+        assert!(!is_valid_xml10_char(c), "{c:?}");
+    }
+
+    assert!(!is_valid_xml11_char('\0'));
+    assert!(!is_valid_xml11_char('\u{FFFE}'));
+    assert!(!is_valid_xml11_char('\u{FFFF}'));
+    assert!(is_valid_xml11_char('\x07'));
+    assert!(is_restricted_xml11_char('\x07'));
+    assert!(is_restricted_xml11_char('\x7F'));
+    assert!(!is_restricted_xml11_char('\u{85}'));
+    assert!(!is_restricted_xml11_char('\n'));
+}
+
+#[test]
+fn test_sanitize_xml10() {
+    use XmlContext::*;
+
+    assert!(matches!(sanitize_xml10("abc é", Text), Cow::Borrowed(_)));
+    assert!(matches!(
+        sanitize_xml10("a\"'\tb\n", Text),
+        Cow::Borrowed(_)
+    ));
+    assert!(matches!(
+        sanitize_xml10("<a>&amp;&#233;</a>", Raw),
+        Cow::Borrowed(_)
+    ));
+
+    for (input, context, expected) in [
+        ("\x07a\x08b\x0Bc\x0Cd\0", Text, "abcd"),
+        ("a\u{FFFE}b\u{FFFF}", Text, "ab"),
+        ("a < b && c > d", Text, "a &lt; b &amp;&amp; c &gt; d"),
+        ("]]>", Text, "]]&gt;"),
+        ("a\r\nb", Text, "a&#13;\nb"),
+        ("&#xD800;", Text, "&amp;#xD800;"),
+        ("\"a\" 'b'", Attribute, "&quot;a&quot; &apos;b&apos;"),
+        ("a\tb\r\n", Attribute, "a&#9;b&#13;&#10;"),
+        ("<a b=\"&#x1;\">\x07</a>", Raw, "<a b=\"\"></a>"),
+        ("&#xD800;&#xdfff;&#55296;", Raw, ""),
+        ("&#xFFFE;&#65535;&#0;&#x110000;", Raw, ""),
+        ("&#99999999999;", Raw, ""),
+        ("&#xE9;&#233;&#x10FFFF;", Raw, "&#xE9;&#233;&#x10FFFF;"),
+        ("&#X1;&#x;&#xZZ;&#1", Raw, "&#X1;&#x;&#xZZ;&#1"),
+        ("&lt;\u{FFFF}&#9;", Raw, "&lt;&#9;"),
+    ] {
+        assert_eq!(sanitize_xml10(input, context), expected, "{input:?}");
+    }
+}
+
+#[test]
+fn test_sanitize_xml11() {
+    use XmlContext::*;
+
+    assert!(matches!(sanitize_xml11("a\u{85}b", Text), Cow::Borrowed(_)));
+
+    for (input, context, expected) in [
+        ("a\x07b\0c", Text, "a&#x7;bc"),
+        ("\x1B[31m\u{9B}\x7F", Text, "&#x1B;[31m&#x9B;&#x7F;"),
+        ("a\u{FFFE}\u{FFFF}<", Attribute, "a&lt;"),
+        ("&#x1;&#0;&#xD800;\x01", Raw, "&#x1;&#x1;"),
+    ] {
+        assert_eq!(sanitize_xml11(input, context), expected, "{input:?}");
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{cmp::EqExt, form_str::FormStr};
+use crate::{
+    cmp::EqExt,
+    form_str::{FormStr, Storage},
+};
 use std::{
     borrow::Borrow,
     fmt::{self, Debug, Display, Formatter},
@@ -61,9 +64,9 @@ impl Hash for StrCi {
     }
 }
 
-impl<F> PartialEq<FormStr<F>> for StrCi {
+impl<F, S: Storage> PartialEq<FormStr<F, S>> for StrCi {
     #[inline]
-    fn eq(&self, other: &FormStr<F>) -> bool {
+    fn eq(&self, other: &FormStr<F, S>) -> bool {
         self.0.eq_ci(other)
     }
 }
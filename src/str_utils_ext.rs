@@ -1,4 +1,5 @@
 use crate::{cmp::EqExt, CharExt};
+use unicode_segmentation::UnicodeSegmentation;
 
 pub trait StrUtilsExt {
     /// Returns true if the given pattern matches a sub-slice of this string slice.
@@ -208,7 +209,51 @@ pub trait StrUtilsExt {
     /// ```
     fn starts_with_ci(&self, pat: &str) -> bool;
 
+    /// Returns the longest prefix of this string slice having at most `max_bytes` bytes,
+    /// without splitting a char.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::StrUtilsExt;
+    ///
+    /// assert_eq!("Café".truncate_bytes(4), "Caf");
+    /// assert_eq!("Café".truncate_bytes(5), "Café");
+    /// ```
+    fn truncate_bytes(&self, max_bytes: usize) -> &str;
+
+    /// Returns the longest prefix of this string slice having at most `max_chars` chars.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::StrUtilsExt;
+    ///
+    /// assert_eq!("Café".truncate_chars(3), "Caf");
+    /// ```
     fn truncate_chars(&self, max_chars: usize) -> &str;
+
+    /// Returns the longest prefix of this string slice having at most `max_graphemes`
+    /// extended grapheme clusters.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::StrUtilsExt;
+    ///
+    /// // "e" followed by a combining acute accent is a single grapheme.
+    /// assert_eq!("Cafe\u{301}s".truncate_graphemes(4), "Cafe\u{301}");
+    /// ```
+    fn truncate_graphemes(&self, max_graphemes: usize) -> &str;
+
+    /// Returns the longest prefix of this string slice having at most `max_units`
+    /// UTF-16 code units, without splitting a surrogate pair.
+    ///
+    /// # Example
+    /// ```
+    /// use str_utils::StrUtilsExt;
+    ///
+    /// assert_eq!("a😀b".truncate_utf16(2), "a");
+    /// assert_eq!("a😀b".truncate_utf16(3), "a😀");
+    /// ```
+    fn truncate_utf16(&self, max_units: usize) -> &str;
 }
 
 impl StrUtilsExt for str {
@@ -295,12 +340,47 @@ impl StrUtilsExt for str {
         starts_with(self, pat, EqExt::eq_ci)
     }
 
+    fn truncate_bytes(&self, max_bytes: usize) -> &str {
+        if self.len() <= max_bytes {
+            return self;
+        }
+
+        let mut idx = max_bytes;
+
+        while !self.is_char_boundary(idx) {
+            idx -= 1;
+        }
+
+        &self[..idx]
+    }
+
     fn truncate_chars(&self, max_chars: usize) -> &str {
         match self.char_indices().nth(max_chars) {
             None => self,
             Some((idx, _)) => &self[..idx],
         }
     }
+
+    fn truncate_graphemes(&self, max_graphemes: usize) -> &str {
+        match self.grapheme_indices(true).nth(max_graphemes) {
+            None => self,
+            Some((idx, _)) => &self[..idx],
+        }
+    }
+
+    fn truncate_utf16(&self, max_units: usize) -> &str {
+        let mut units = 0;
+
+        for (idx, c) in self.char_indices() {
+            units += c.len_utf16();
+
+            if units > max_units {
+                return &self[..idx];
+            }
+        }
+
+        self
+    }
 }
 
 #[derive(Eq, PartialEq)]
@@ -412,3 +492,14 @@ fn starts_with_ci_works() {
     assert!(!"Café Arabica".starts_with_ci("CAFE"));
     assert!("Café Arabica".to_owned().starts_with_ci("caFÉ"));
 }
+
+#[test]
+fn truncate_works() {
+    assert_eq!("été".truncate_bytes(0), "");
+    assert_eq!("été".truncate_bytes(1), "");
+    assert_eq!("été".truncate_bytes(2), "é");
+    assert_eq!("été".truncate_chars(2), "ét");
+    assert_eq!("e\u{301}te\u{301}".truncate_graphemes(1), "e\u{301}");
+    assert_eq!("😀😀".truncate_utf16(1), "");
+    assert_eq!("😀😀".truncate_utf16(4), "😀😀");
+}
@@ -0,0 +1,222 @@
+use super::{
+    parser::{is_sub_delim, is_unreserved},
+    Result, UrlError,
+};
+use std::borrow::Cow;
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// The chars kept as is by [encode_component], depending on the URL component. Letters,
+/// digits, `-`, `.`, `_` and `~` are always kept, non ascii chars and `%` are always encoded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EncodeSet {
+    /// A fragment, keeps the sub-delims, `:`, `@`, `/` and `?`.
+    Fragment,
+    /// A key or a value of an `application/x-www-form-urlencoded` query. Only keeps `*`, and
+    /// a space becomes `+`.
+    Form,
+    /// A path, keeps the sub-delims, `:`, `@` and `/`.
+    Path,
+    /// A single path segment, like [EncodeSet::Path] but `/` is encoded.
+    PathSegment,
+    /// A key or a value of a query, keeps the sub-delims except `&`, `=` and `+`, and keeps
+    /// `:`, `@`, `/` and `?`.
+    Query,
+    /// A user name or a password, keeps the sub-delims.
+    Userinfo,
+}
+
+impl EncodeSet {
+    fn keeps(self, b: u8) -> bool {
+        let c = b as char;
+
+        if !b.is_ascii() {
+            return false;
+        }
+
+        match self {
+            Self::Form => c.is_ascii_alphanumeric() || matches!(c, '*' | '-' | '.' | '_'),
+            Self::Fragment => {
+                is_unreserved(c) || is_sub_delim(c) || matches!(c, ':' | '@' | '/' | '?')
+            }
+            Self::Path => is_unreserved(c) || is_sub_delim(c) || matches!(c, ':' | '@' | '/'),
+            Self::PathSegment => is_unreserved(c) || is_sub_delim(c) || matches!(c, ':' | '@'),
+            Self::Query => {
+                is_unreserved(c)
+                    || (is_sub_delim(c) && !matches!(c, '&' | '=' | '+'))
+                    || matches!(c, ':' | '@' | '/' | '?')
+            }
+            Self::Userinfo => is_unreserved(c) || is_sub_delim(c),
+        }
+    }
+}
+
+/// Percent-encode the bytes not kept by the [EncodeSet], borrowing the string when nothing
+/// needs to be encoded.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{encode_component, EncodeSet};
+///
+/// assert_eq!(encode_component("a b/é", EncodeSet::PathSegment), "a%20b%2F%C3%A9");
+/// assert_eq!(encode_component("a b&c", EncodeSet::Form), "a+b%26c");
+/// assert_eq!(encode_component("a/b", EncodeSet::Path), "a/b");
+/// ```
+pub fn encode_component(s: &str, set: EncodeSet) -> Cow<'_, str> {
+    let Some(first) = s.bytes().position(|b| !set.keeps(b)) else {
+        return Cow::Borrowed(s);
+    };
+
+    let mut out = String::with_capacity(s.len() + 16);
+    out.push_str(&s[..first]);
+
+    for &b in &s.as_bytes()[first..] {
+        if set.keeps(b) {
+            out.push(b as char);
+        } else if b == b' ' && set == EncodeSet::Form {
+            out.push('+');
+        } else {
+            out.push('%');
+            out.push(HEX[(b >> 4) as usize] as char);
+            out.push(HEX[(b & 0xF) as usize] as char);
+        }
+    }
+
+    Cow::Owned(out)
+}
+
+/// Decodes the `%XX` sequences, borrowing the string when there is none.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{decode, UrlError};
+///
+/// assert_eq!(decode("a%20b%C3%A9+c").unwrap(), "a bé+c");
+/// assert_eq!(decode("%zz"), Err(UrlError::InvalidPercentEncoding));
+/// assert_eq!(decode("%FF"), Err(UrlError::InvalidUtf8));
+/// ```
+pub fn decode(s: &str) -> Result<Cow<'_, str>> {
+    decode_with(s, false)
+}
+
+/// Decodes an `application/x-www-form-urlencoded` key or value like [decode], a `+` is
+/// decoded into a space.
+///
+/// # Example
+/// ```
+/// use str_utils::url::decode_form;
+///
+/// assert_eq!(decode_form("a+b%2B").unwrap(), "a b+");
+/// ```
+pub fn decode_form(s: &str) -> Result<Cow<'_, str>> {
+    decode_with(s, true)
+}
+
+fn decode_with(s: &str, plus_is_space: bool) -> Result<Cow<'_, str>> {
+    if !(s.contains('%') || plus_is_space && s.contains('+')) {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let b = s.as_bytes();
+    let mut out = Vec::with_capacity(b.len());
+    let mut i = 0;
+
+    while i < b.len() {
+        match b[i] {
+            b'%' => {
+                out.push(decode_hex(&b[i + 1..]).ok_or(UrlError::InvalidPercentEncoding)?);
+                i += 3;
+                continue;
+            }
+            b'+' if plus_is_space => out.push(b' '),
+            v => out.push(v),
+        }
+
+        i += 1;
+    }
+
+    String::from_utf8(out)
+        .map(Cow::Owned)
+        .map_err(|_| UrlError::InvalidUtf8)
+}
+
+/// Decodes the byte of a `%XX` sequence, `b` starts after the `%`.
+pub(crate) fn decode_hex(b: &[u8]) -> Option<u8> {
+    let h = (*b.first()? as char).to_digit(16)?;
+    let l = (*b.get(1)? as char).to_digit(16)?;
+
+    Some((h * 16 + l) as u8)
+}
+
+/// Decodes the `%XX` sequences, the invalid sequences are kept as is.
+pub(crate) fn percent_decode(s: &str) -> Cow<'_, [u8]> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s.as_bytes());
+    }
+
+    let b = s.as_bytes();
+    let mut out = Vec::with_capacity(b.len());
+    let mut i = 0;
+
+    while i < b.len() {
+        if b[i] == b'%' {
+            if let Some(v) = decode_hex(&b[i + 1..]) {
+                out.push(v);
+                i += 3;
+                continue;
+            }
+        }
+
+        out.push(b[i]);
+        i += 1;
+    }
+
+    Cow::Owned(out)
+}
+
+#[test]
+fn test_encode_component() {
+    use EncodeSet::*;
+
+    let all = "a-._~!$&'()*+,;=:@/?# %é";
+
+    for (set, expected) in [
+        (Fragment, "a-._~!$&'()*+,;=:@/?%23%20%25%C3%A9"),
+        (
+            Form,
+            "a-._%7E%21%24%26%27%28%29*%2B%2C%3B%3D%3A%40%2F%3F%23+%25%C3%A9",
+        ),
+        (Path, "a-._~!$&'()*+,;=:@/%3F%23%20%25%C3%A9"),
+        (PathSegment, "a-._~!$&'()*+,;=:@%2F%3F%23%20%25%C3%A9"),
+        (Query, "a-._~!$%26'()*%2B,;%3D:@/?%23%20%25%C3%A9"),
+        (Userinfo, "a-._~!$&'()*+,;=%3A%40%2F%3F%23%20%25%C3%A9"),
+    ] {
+        let encoded = encode_component(all, set);
+
+        assert_eq!(encoded, expected, "{set:?}");
+
+        let decoded = match set {
+            Form => decode_form(&encoded),
+            _ => decode(&encoded),
+        };
+
+        assert_eq!(decoded.unwrap(), all, "{set:?}");
+    }
+
+    assert!(matches!(encode_component("abc", Form), Cow::Borrowed(_)));
+    assert!(matches!(encode_component("", Query), Cow::Borrowed(_)));
+}
+
+#[test]
+fn test_decode() {
+    assert!(matches!(decode("a+b"), Ok(Cow::Borrowed("a+b"))));
+    assert!(matches!(decode_form("ab"), Ok(Cow::Borrowed("ab"))));
+    assert_eq!(decode_form("a+b").unwrap(), "a b");
+    assert_eq!(decode("%41%2f%2F").unwrap(), "A//");
+    assert_eq!(decode("%"), Err(UrlError::InvalidPercentEncoding));
+    assert_eq!(decode("a%4"), Err(UrlError::InvalidPercentEncoding));
+    assert_eq!(decode("%C3"), Err(UrlError::InvalidUtf8));
+
+    // the decoding must not split a char.
+    assert_eq!(decode("é%41é").unwrap(), "éAé");
+}
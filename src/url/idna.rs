@@ -0,0 +1,454 @@
+use super::{Result, UrlError};
+use crate::range_table;
+use unicode_normalization::UnicodeNormalization;
+
+/// The maximum length of a label in bytes, once converted to ASCII.
+const MAX_LABEL_LEN: usize = 63;
+/// The maximum length of a host in bytes, once converted to ASCII, without the trailing dot.
+const MAX_HOST_LEN: usize = 253;
+
+/// The prefix of the labels encoded with punycode.
+const ACE_PREFIX: &str = "xn--";
+
+enum Status {
+    Valid,
+    Mapped(&'static str),
+    Ignored,
+    Disallowed,
+}
+
+/// The status of a char in the table generated by build.rs, see `write_idna_map`.
+fn status(c: char) -> Status {
+    static TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/idna.bin"));
+
+    match range_table::lookup(TABLE, c) {
+        (0, _) => Status::Valid,
+        (1, mapping) => Status::Mapped(mapping),
+        (2, _) => Status::Ignored,
+        _ => Status::Disallowed,
+    }
+}
+
+/// Convert a host to its ASCII form following UTS #46 with the STD3 rules and nontransitional
+/// processing, such as "Café.Example" to "xn--caf-dma.example".
+///
+/// The chars are mapped with the UTS #46 mapping table, such as uppercase and full-width chars
+/// to lowercase, the labels containing non-ASCII chars are encoded with punycode and prefixed
+/// by "xn--". Labels must have 1 to 63 bytes, must not start or end with `-`, and the host
+/// must have up to 253 bytes. A trailing dot is kept. Labels already starting with "xn--" must
+/// be valid punycode.
+///
+/// The joiners U+200C and U+200D are only accepted in the contexts of the CheckJoiners rule,
+/// such as after a virama. The bidi rules of IDNA 2008 are not checked.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{host_to_ascii, UrlError};
+///
+/// assert_eq!(host_to_ascii("BÜCHER.de").unwrap(), "xn--bcher-kva.de");
+/// assert_eq!(host_to_ascii("example.com").unwrap(), "example.com");
+/// assert_eq!(host_to_ascii("a_b.com"), Err(UrlError::InvalidChar('_')));
+/// ```
+pub fn host_to_ascii(host: &str) -> Result<String> {
+    let mut mapped = String::with_capacity(host.len());
+
+    for c in host.chars() {
+        match status(c) {
+            Status::Valid => mapped.push(c),
+            Status::Mapped(m) => mapped.push_str(m),
+            Status::Ignored => {}
+            Status::Disallowed => return Err(UrlError::InvalidChar(c)),
+        }
+    }
+
+    let mapped = mapped.nfc().collect::<String>();
+    let (labels, root) = match mapped.strip_suffix('.') {
+        Some(labels) => (labels, true),
+        None => (mapped.as_str(), false),
+    };
+
+    let mut out = String::with_capacity(labels.len());
+
+    for label in labels.split('.') {
+        if !out.is_empty() {
+            out.push('.');
+        }
+
+        if let Some(encoded) = label.strip_prefix(ACE_PREFIX) {
+            let decoded = punycode_decode(encoded).ok_or(UrlError::InvalidPunycode)?;
+
+            // the ASCII form of a label is unique.
+            if decoded.is_ascii()
+                || punycode_encode(&decoded).as_deref() != Some(encoded)
+                || decoded.nfc().ne(decoded.chars())
+                || decoded.chars().any(|c| !matches!(status(c), Status::Valid))
+            {
+                return Err(UrlError::InvalidPunycode);
+            }
+
+            check_label(&decoded)?;
+            out.push_str(label);
+        } else {
+            check_label(label)?;
+
+            if label.is_ascii() {
+                out.push_str(label);
+            } else {
+                out.push_str(ACE_PREFIX);
+                out.push_str(&punycode_encode(label).ok_or(UrlError::InvalidPunycode)?);
+            }
+        }
+
+        if out.len() - out.rfind('.').map_or(0, |i| i + 1) > MAX_LABEL_LEN {
+            return Err(UrlError::LabelTooLong);
+        }
+    }
+
+    if out.len() > MAX_HOST_LEN {
+        return Err(UrlError::HostTooLong);
+    }
+
+    if root {
+        out.push('.');
+    }
+
+    Ok(out)
+}
+
+/// Convert a host to its Unicode form for display, such as "xn--caf-dma.EXAMPLE" to
+/// "café.example". The host is validated and normalized by [host_to_ascii] first.
+///
+/// # Example
+/// ```
+/// use str_utils::url::host_to_unicode;
+///
+/// assert_eq!(host_to_unicode("xn--bcher-kva.DE").unwrap(), "bücher.de");
+/// assert_eq!(host_to_unicode("Bücher.de").unwrap(), "bücher.de");
+/// ```
+pub fn host_to_unicode(host: &str) -> Result<String> {
+    let ascii = host_to_ascii(host)?;
+    let mut out = String::with_capacity(ascii.len());
+
+    for (i, label) in ascii.split('.').enumerate() {
+        if i > 0 {
+            out.push('.');
+        }
+
+        match label.strip_prefix(ACE_PREFIX) {
+            Some(encoded) => {
+                out.push_str(&punycode_decode(encoded).ok_or(UrlError::InvalidPunycode)?)
+            }
+            None => out.push_str(label),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Check the hyphens, the first char and the joiners of a mapped label.
+fn check_label(label: &str) -> Result<()> {
+    if label.is_empty()
+        || label.starts_with('-')
+        || label.ends_with('-')
+        || label.get(2..4) == Some("--")
+        || label
+            .chars()
+            .next()
+            .is_some_and(unicode_normalization::char::is_combining_mark)
+    {
+        return Err(UrlError::InvalidLabel);
+    }
+
+    check_joiners(label)
+}
+
+const JOINING_LEFT: u8 = 1;
+const JOINING_RIGHT: u8 = 2;
+const JOINING_TRANSPARENT: u8 = 4;
+const VIRAMA: u8 = 8;
+
+/// The joining flags of a char in the table generated by build.rs, see `write_joining_map`.
+fn joining(c: char) -> u8 {
+    static TABLE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/joining.bin"));
+
+    range_table::lookup(TABLE, c).0
+}
+
+/// The CheckJoiners rule of RFC 5892, appendix A: a joiner must follow a virama, a ZWNJ can
+/// also be between a left or dual joining char and a right or dual joining char, ignoring the
+/// transparent chars.
+fn check_joiners(label: &str) -> Result<()> {
+    let chars = label.chars().collect::<Vec<_>>();
+
+    for (i, c) in chars.iter().enumerate() {
+        if !matches!(c, '\u{200C}' | '\u{200D}') {
+            continue;
+        }
+
+        if i > 0 && joining(chars[i - 1]) & VIRAMA != 0 {
+            continue;
+        }
+
+        if *c == '\u{200C}'
+            && joins(chars[..i].iter().rev(), JOINING_LEFT)
+            && joins(chars[i + 1..].iter(), JOINING_RIGHT)
+        {
+            continue;
+        }
+
+        return Err(UrlError::InvalidLabel);
+    }
+
+    Ok(())
+}
+
+/// Returns true if the first char which is not transparent has the joining flag.
+fn joins<'a>(chars: impl Iterator<Item = &'a char>, flag: u8) -> bool {
+    chars
+        .map(|c| joining(*c))
+        .find(|j| j & JOINING_TRANSPARENT == 0)
+        .is_some_and(|j| j & flag != 0)
+}
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+    let mut delta = if first { delta / DAMP } else { delta / 2 };
+    let mut k = 0;
+
+    delta += delta / points;
+
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn digit(d: u32) -> char {
+    match d {
+        0..26 => (b'a' + d as u8) as char,
+        _ => (b'0' + (d - 26) as u8) as char,
+    }
+}
+
+/// Encode a label following RFC 3492, `None` on overflow.
+fn punycode_encode(input: &str) -> Option<String> {
+    let chars = input.chars().map(u32::from).collect::<Vec<_>>();
+    let mut out = input.chars().filter(char::is_ascii).collect::<String>();
+
+    let basic = out.len() as u32;
+    let mut handled = basic;
+
+    if basic > 0 {
+        out.push('-');
+    }
+
+    let (mut n, mut delta, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+
+    while (handled as usize) < chars.len() {
+        let m = chars.iter().copied().filter(|&c| c >= n).min()?;
+
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+
+        for &c in &chars {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+
+                loop {
+                    let t = threshold(k, bias);
+
+                    if q < t {
+                        break;
+                    }
+
+                    out.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+
+                out.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(out)
+}
+
+/// Decode a label following RFC 3492, `None` when it is not valid.
+fn punycode_decode(input: &str) -> Option<String> {
+    let (basic, encoded) = match input.rfind('-') {
+        Some(idx) => (&input[..idx], &input[idx + 1..]),
+        None => ("", input),
+    };
+
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut out = basic.chars().collect::<Vec<_>>();
+    let (mut n, mut i, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+    let mut bytes = encoded.bytes().peekable();
+
+    while bytes.peek().is_some() {
+        let old_i = i;
+        let mut w = 1u32;
+        let mut k = BASE;
+
+        loop {
+            let d = match bytes.next()? {
+                b @ b'a'..=b'z' => b - b'a',
+                b @ b'A'..=b'Z' => b - b'A',
+                b @ b'0'..=b'9' => b - b'0' + 26,
+                _ => return None,
+            } as u32;
+
+            i = i.checked_add(d.checked_mul(w)?)?;
+
+            let t = threshold(k, bias);
+
+            if d < t {
+                break;
+            }
+
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let len = out.len() as u32 + 1;
+
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len)?;
+        i %= len;
+
+        out.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+
+    Some(out.into_iter().collect())
+}
+
+#[test]
+fn test_punycode() {
+    for (decoded, encoded) in [
+        ("bücher", "bcher-kva"),
+        ("münchen", "mnchen-3ya"),
+        ("café", "caf-dma"),
+        ("ü", "tda"),
+        ("例え", "r8jz45g"),
+        ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+        (
+            "почемужеонинеговорятпорусски",
+            "b1abfaaepdrnnbgefbadotcwatmq2g4l",
+        ),
+    ] {
+        assert_eq!(punycode_encode(decoded).unwrap(), encoded, "{decoded}");
+        assert_eq!(punycode_decode(encoded).unwrap(), decoded, "{encoded}");
+    }
+
+    assert_eq!(punycode_decode("a-é"), None);
+    assert_eq!(punycode_decode("a-!"), None);
+    assert_eq!(punycode_decode("99999999999"), None);
+}
+
+#[test]
+fn test_host_to_ascii() {
+    for (input, ascii) in [
+        ("example.com", "example.com"),
+        ("EXAMPLE.com.", "example.com."),
+        ("café.example", "xn--caf-dma.example"),
+        ("CAFÉ.example", "xn--caf-dma.example"),
+        ("cafe\u{301}.example", "xn--caf-dma.example"),
+        ("ｅｘａｍｐｌｅ．com", "example.com"),
+        ("例え。テスト", "xn--r8jz45g.xn--zckzah"),
+        ("a\u{AD}b.com", "ab.com"),
+        ("straße.de", "xn--strae-oqa.de"),
+        ("xn--caf-dma.EXAMPLE", "xn--caf-dma.example"),
+        ("ⅻ.com", "xii.com"),
+        // the mappings of the UTS #46 table, such as Cherokee folded to uppercase.
+        ("\u{AB70}.com", "xn--58d.com"),
+        ("\u{1E9E}.de", "xn--zca.de"),
+        ("\u{FB01}.com", "fi.com"),
+        // a joiner after a virama, a ZWNJ between joining Arabic letters.
+        ("\u{915}\u{94D}\u{200D}", "xn--11b6iy14e"),
+        ("\u{628}\u{200C}\u{628}", "xn--ngba799q"),
+    ] {
+        assert_eq!(host_to_ascii(input).unwrap(), ascii, "{input}");
+    }
+
+    let label = "a".repeat(63);
+
+    assert!(host_to_ascii(&label).is_ok());
+    assert_eq!(host_to_ascii(&"a".repeat(64)), Err(UrlError::LabelTooLong));
+    assert_eq!(
+        host_to_ascii(&format!("{}é", "a".repeat(60))),
+        Err(UrlError::LabelTooLong)
+    );
+    assert!(host_to_ascii(&[&label[..]; 4].join(".")[..253]).is_ok());
+    assert_eq!(
+        host_to_ascii(&[&label[..]; 4].join(".")[..254]),
+        Err(UrlError::HostTooLong)
+    );
+
+    for (input, e) in [
+        ("", UrlError::InvalidLabel),
+        ("a..b", UrlError::InvalidLabel),
+        (".a", UrlError::InvalidLabel),
+        ("-a.com", UrlError::InvalidLabel),
+        ("a-.com", UrlError::InvalidLabel),
+        ("ab--c.com", UrlError::InvalidLabel),
+        ("\u{301}a.com", UrlError::InvalidLabel),
+        ("xn--a.com", UrlError::InvalidPunycode),
+        ("xn--caf-dma9.com", UrlError::InvalidPunycode),
+        ("xn--abc-.com", UrlError::InvalidPunycode),
+        ("a b.com", UrlError::InvalidChar(' ')),
+        ("a_b.com", UrlError::InvalidChar('_')),
+        ("[::1]", UrlError::InvalidChar('[')),
+        ("a\u{2488}com", UrlError::InvalidChar('\u{2488}')),
+        ("a\u{FFFD}.com", UrlError::InvalidChar('\u{FFFD}')),
+        ("\u{E000}.com", UrlError::InvalidChar('\u{E000}')),
+        ("a\u{200D}b.com", UrlError::InvalidLabel),
+        ("a\u{200C}b.com", UrlError::InvalidLabel),
+        ("\u{628}\u{200C}.com", UrlError::InvalidLabel),
+    ] {
+        assert_eq!(host_to_ascii(input), Err(e), "{input}");
+    }
+}
+
+#[test]
+fn test_host_to_unicode() {
+    assert_eq!(
+        host_to_unicode("xn--caf-dma.example").unwrap(),
+        "café.example"
+    );
+    assert_eq!(host_to_unicode("CAFÉ.Example.").unwrap(), "café.example.");
+    assert_eq!(host_to_unicode("xn--r8jz45g").unwrap(), "例え");
+    assert_eq!(host_to_unicode("xn--a"), Err(UrlError::InvalidPunycode));
+}
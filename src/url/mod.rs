@@ -0,0 +1,110 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+mod encoding;
+mod idna;
+mod parser;
+mod query;
+mod slug;
+
+pub use encoding::{decode, decode_form, encode_component, EncodeSet};
+pub(crate) use encoding::{decode_hex, percent_decode};
+pub use idna::{host_to_ascii, host_to_unicode};
+pub use parser::Url;
+pub use query::QueryString;
+pub use slug::{
+    slugify, unique_slug, validate_slug, validate_slug_with, SlugOptions, SlugSeparator,
+    STOP_WORDS_EN, STOP_WORDS_FR,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UrlError {
+    /// A query pair without key, such as "=1".
+    EmptyQueryKey,
+    /// An empty query pair, such as "a=1&&b=2" or "a=1&".
+    EmptyQueryPair,
+    /// A host longer than 253 bytes once converted to ASCII.
+    HostTooLong,
+    InvalidChar(char),
+    /// An IPv6 host which is not valid, or not closed by `]`.
+    InvalidHost,
+    /// An empty host label, or a label starting or ending with `-`, having `--` at the third
+    /// position without being punycode, or starting with a combining mark.
+    InvalidLabel,
+    /// A `%` not followed by two hexadecimal digits, such as "%zz".
+    InvalidPercentEncoding,
+    /// A port which is not a number up to 65535.
+    InvalidPort,
+    /// A label starting with "xn--" which is not valid punycode.
+    InvalidPunycode,
+    /// A scheme not starting with a letter, or containing chars other than letters, digits,
+    /// `+`, `-` and `.`.
+    InvalidScheme,
+    /// An empty slug, or a slug starting or ending with its separator or containing two of
+    /// them in a row, such as "a--b".
+    InvalidSlug,
+    /// The decoded bytes are not valid UTF-8.
+    InvalidUtf8,
+    /// A host label longer than 63 bytes once converted to ASCII.
+    LabelTooLong,
+    /// An empty host in an URL such as "http://".
+    MissingHost,
+    MissingScheme,
+}
+
+impl Debug for UrlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyQueryKey => f.write_str("empty query key"),
+            Self::EmptyQueryPair => f.write_str("empty query pair"),
+            Self::HostTooLong => f.write_str("host too long"),
+            Self::InvalidChar(c) => write!(f, "invalid url char {c}"),
+            Self::InvalidHost => f.write_str("invalid url host"),
+            Self::InvalidLabel => f.write_str("invalid host label"),
+            Self::InvalidPercentEncoding => f.write_str("invalid percent encoding"),
+            Self::InvalidPort => f.write_str("invalid url port"),
+            Self::InvalidPunycode => f.write_str("invalid punycode"),
+            Self::InvalidScheme => f.write_str("invalid url scheme"),
+            Self::InvalidSlug => f.write_str("invalid slug"),
+            Self::InvalidUtf8 => f.write_str("invalid utf-8"),
+            Self::LabelTooLong => f.write_str("host label too long"),
+            Self::MissingHost => f.write_str("missing url host"),
+            Self::MissingScheme => f.write_str("missing url scheme"),
+        }
+    }
+}
+
+impl Display for UrlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for UrlError {}
+
+pub type Result<T> = std::result::Result<T, UrlError>;
+
+/// Ensure all chars in the string are accepted for an url.
+pub fn validate_accepted_url_chars(s: &str) -> Result<()> {
+    let s = s.trim();
+
+    const VALID_CHARS: [char; 23] = [
+        '-', '.', '_', '~', ':', '/', '?', '#', '[', ']', '@', '!', '$', '&', '\'', '(', ')', '*',
+        '+', ',', ';', '=', '%',
+    ];
+
+    for c in s.chars() {
+        //ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~:/?#[]@!$&'()*+,;=%
+        if !c.is_ascii_alphanumeric() && !VALID_CHARS.contains(&c) {
+            return Err(UrlError::InvalidChar(c));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_accepted_url_chars() {
+    assert!(
+        validate_accepted_url_chars("http://est.jon/v?q=param&other=param%20#test=123").is_ok()
+    );
+}
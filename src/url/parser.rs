@@ -0,0 +1,326 @@
+use super::{decode_hex, Result, UrlError};
+use std::{
+    fmt::{self, Display, Formatter},
+    net::Ipv6Addr,
+};
+
+/// Schemes which require a host.
+const HOST_SCHEMES: [&str; 5] = ["ftp", "http", "https", "ws", "wss"];
+
+/// An absolute URL validated following RFC 3986, borrowing its components from the parsed
+/// string.
+///
+/// The components are not normalized, the scheme and the host must be compared case
+/// insensitively.
+///
+/// # Example
+/// ```
+/// use str_utils::url::Url;
+///
+/// let url = Url::parse("https://user@example.com:8080/a/b?q=1#top").unwrap();
+///
+/// assert_eq!(url.scheme(), "https");
+/// assert_eq!(url.authority(), Some("user@example.com:8080"));
+/// assert_eq!(url.userinfo(), Some("user"));
+/// assert_eq!(url.host(), Some("example.com"));
+/// assert_eq!(url.port(), Some(8080));
+/// assert_eq!(url.path(), "/a/b");
+/// assert_eq!(url.query(), Some("q=1"));
+/// assert_eq!(url.fragment(), Some("top"));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Url<'a> {
+    s: &'a str,
+    scheme: &'a str,
+    authority: Option<&'a str>,
+    userinfo: Option<&'a str>,
+    host: Option<&'a str>,
+    port: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Url<'a> {
+    /// Parse and validate an absolute URL.
+    pub fn parse(s: &'a str) -> Result<Self> {
+        let (scheme, rest) = s.split_once(':').ok_or(UrlError::MissingScheme)?;
+
+        if !is_scheme(scheme) {
+            return Err(UrlError::InvalidScheme);
+        }
+
+        let (rest, fragment) = split(rest, '#');
+        let (rest, query) = split(rest, '?');
+
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(r) => {
+                let end = r.find('/').unwrap_or(r.len());
+                (Some(&r[..end]), &r[end..])
+            }
+            None => (None, rest),
+        };
+
+        let mut url = Self {
+            s,
+            scheme,
+            authority,
+            userinfo: None,
+            host: None,
+            port: None,
+            path,
+            query,
+            fragment,
+        };
+
+        if let Some(authority) = authority {
+            url.parse_authority(authority)?;
+        }
+
+        if url.host.is_none_or(str::is_empty)
+            && HOST_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme))
+        {
+            return Err(UrlError::MissingHost);
+        }
+
+        check_chars(path, &[':', '@', '/'])?;
+
+        if let Some(q) = query {
+            check_chars(q, &[':', '@', '/', '?'])?;
+        }
+
+        if let Some(f) = fragment {
+            check_chars(f, &[':', '@', '/', '?'])?;
+        }
+
+        Ok(url)
+    }
+
+    fn parse_authority(&mut self, authority: &'a str) -> Result<()> {
+        let host_port = match authority.rsplit_once('@') {
+            Some((userinfo, host_port)) => {
+                check_chars(userinfo, &[':'])?;
+                self.userinfo = Some(userinfo);
+                host_port
+            }
+            None => authority,
+        };
+
+        let (host, port) = if let Some(literal) = host_port.strip_prefix('[') {
+            let (ip, rest) = literal.split_once(']').ok_or(UrlError::InvalidHost)?;
+
+            if ip.parse::<Ipv6Addr>().is_err() {
+                return Err(UrlError::InvalidHost);
+            }
+
+            let port = match rest {
+                "" => None,
+                _ => Some(rest.strip_prefix(':').ok_or(UrlError::InvalidHost)?),
+            };
+
+            (&host_port[..ip.len() + 2], port)
+        } else {
+            let (host, port) = split(host_port, ':');
+
+            check_chars(host, &[])?;
+            (host, port)
+        };
+
+        // an empty port is the same as no port.
+        let port = port.filter(|p| !p.is_empty());
+
+        if let Some(port) = port {
+            if !port.bytes().all(|b| b.is_ascii_digit()) || port.parse::<u16>().is_err() {
+                return Err(UrlError::InvalidPort);
+            }
+        }
+
+        self.host = Some(host);
+        self.port = port;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    /// The part after "//", such as "user@example.com:8080".
+    #[inline]
+    pub fn authority(&self) -> Option<&'a str> {
+        self.authority
+    }
+
+    /// The part after "#".
+    #[inline]
+    pub fn fragment(&self) -> Option<&'a str> {
+        self.fragment
+    }
+
+    /// The host of the authority, an IPv6 address keeps its brackets.
+    #[inline]
+    pub fn host(&self) -> Option<&'a str> {
+        self.host
+    }
+
+    #[inline]
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    #[inline]
+    pub fn port(&self) -> Option<u16> {
+        self.port.and_then(|p| p.parse().ok())
+    }
+
+    /// The port as written, such as "08080".
+    #[inline]
+    pub fn port_str(&self) -> Option<&'a str> {
+        self.port
+    }
+
+    /// The part after "?", without the fragment.
+    #[inline]
+    pub fn query(&self) -> Option<&'a str> {
+        self.query
+    }
+
+    #[inline]
+    pub fn scheme(&self) -> &'a str {
+        self.scheme
+    }
+
+    /// The part before "@" in the authority, such as "user:password".
+    #[inline]
+    pub fn userinfo(&self) -> Option<&'a str> {
+        self.userinfo
+    }
+}
+
+impl Display for Url<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.s)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Url<'a> {
+    type Error = UrlError;
+
+    #[inline]
+    fn try_from(value: &'a str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+/// Ensure every char is unreserved, a sub-delim, a valid `%XX` sequence or in `extra`.
+fn check_chars(s: &str, extra: &[char]) -> Result<()> {
+    let b = s.as_bytes();
+
+    for (i, c) in s.char_indices() {
+        if c == '%' {
+            if decode_hex(&b[i + 1..]).is_none() {
+                return Err(UrlError::InvalidPercentEncoding);
+            }
+        } else if !is_unreserved(c) && !is_sub_delim(c) && !extra.contains(&c) {
+            return Err(UrlError::InvalidChar(c));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+pub(crate) fn is_sub_delim(c: char) -> bool {
+    matches!(
+        c,
+        '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '='
+    )
+}
+
+pub(crate) fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~')
+}
+
+fn split(s: &str, c: char) -> (&str, Option<&str>) {
+    match s.split_once(c) {
+        Some((l, r)) => (l, Some(r)),
+        None => (s, None),
+    }
+}
+
+#[test]
+fn test_parse() {
+    let url = Url::parse("mailto:john@example.com").unwrap();
+
+    assert_eq!(url.scheme(), "mailto");
+    assert_eq!(url.authority(), None);
+    assert_eq!(url.host(), None);
+    assert_eq!(url.path(), "john@example.com");
+
+    let url = Url::parse("http://[::1]:80/").unwrap();
+
+    assert_eq!(url.host(), Some("[::1]"));
+    assert_eq!(url.port(), Some(80));
+    assert_eq!(url.path(), "/");
+
+    let url = Url::parse("file:///etc/hosts").unwrap();
+
+    assert_eq!(url.host(), Some(""));
+    assert_eq!(url.path(), "/etc/hosts");
+
+    let url = Url::parse("HTTP://a:@b.c:?#").unwrap();
+
+    assert_eq!(url.userinfo(), Some("a:"));
+    assert_eq!(url.host(), Some("b.c"));
+    assert_eq!(url.port(), None);
+    assert_eq!(url.query(), Some(""));
+    assert_eq!(url.fragment(), Some(""));
+
+    let url = Url::parse("urn:isbn:0451450523").unwrap();
+
+    assert_eq!(url.path(), "isbn:0451450523");
+    assert_eq!(url.to_string(), "urn:isbn:0451450523");
+
+    for ok in [
+        "http://est.jon/v?q=param&other=param%20#test=123",
+        "https://example.com",
+        "https://example.com/a%2Fb/?q=a/b?c",
+        "ftp://1.2.3.4:21/",
+        "git+ssh://git@github.com/a/b.git",
+        "data:text/plain,hello",
+    ] {
+        assert!(Url::parse(ok).is_ok(), "{ok}");
+    }
+
+    for (input, e) in [
+        ("example.com", UrlError::MissingScheme),
+        ("://a", UrlError::InvalidScheme),
+        ("1http://a", UrlError::InvalidScheme),
+        ("ht tp://a", UrlError::InvalidScheme),
+        ("http://a/%zz", UrlError::InvalidPercentEncoding),
+        ("http://a/%2", UrlError::InvalidPercentEncoding),
+        ("http://a/?%G0", UrlError::InvalidPercentEncoding),
+        ("http://a:99999", UrlError::InvalidPort),
+        ("http://a:8o", UrlError::InvalidPort),
+        ("http://a:-1", UrlError::InvalidPort),
+        ("http://[::g]/", UrlError::InvalidHost),
+        ("http://[::1/", UrlError::InvalidHost),
+        ("http://[::1]x/", UrlError::InvalidHost),
+        ("http://", UrlError::MissingHost),
+        ("https:/a", UrlError::MissingHost),
+        ("http://a b/", UrlError::InvalidChar(' ')),
+        ("http://a/b c", UrlError::InvalidChar(' ')),
+        ("http://a/é", UrlError::InvalidChar('é')),
+        ("http://a/#a#b", UrlError::InvalidChar('#')),
+        ("http://a/[b]", UrlError::InvalidChar('[')),
+        ("http://a@b@c/", UrlError::InvalidChar('@')),
+    ] {
+        assert_eq!(Url::parse(input), Err(e), "{input}");
+    }
+}
@@ -0,0 +1,255 @@
+use super::{decode_form, encode_component, EncodeSet, Result, UrlError};
+use crate::str_ci::StrCi;
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// The ordered key / value pairs of an `application/x-www-form-urlencoded` query string, such
+/// as "a=1&b=2". A key can be repeated.
+///
+/// # Example
+/// ```
+/// use str_utils::{str_ci::StrCi, url::QueryString};
+///
+/// let mut q = QueryString::parse("?Name=Caf%C3%A9&tag=a&tag=b+c").unwrap();
+///
+/// assert_eq!(q.get("Name"), Some("Café"));
+/// assert_eq!(q.get_ci(StrCi::new("NAME")), Some("Café"));
+/// assert_eq!(q.get_all("tag").collect::<Vec<_>>(), ["a", "b c"]);
+///
+/// q.set("tag", "x&y");
+///
+/// assert_eq!(q.to_string(), "Name=Caf%C3%A9&tag=x%26y");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct QueryString {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a query string, a leading `?` is ignored. A key without `=` has an empty value.
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.strip_prefix('?').unwrap_or(s);
+        let mut pairs = Vec::new();
+
+        if s.is_empty() {
+            return Ok(Self { pairs });
+        }
+
+        for pair in s.split('&') {
+            if pair.is_empty() {
+                return Err(UrlError::EmptyQueryPair);
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            if key.is_empty() {
+                return Err(UrlError::EmptyQueryKey);
+            }
+
+            if let Some(c) = pair.chars().find(|c| !is_query_char(*c)) {
+                return Err(UrlError::InvalidChar(c));
+            }
+
+            pairs.push((
+                decode_form(key)?.into_owned(),
+                decode_form(value)?.into_owned(),
+            ));
+        }
+
+        Ok(Self { pairs })
+    }
+
+    /// Add a pair at the end, keeping the pairs with the same key.
+    pub fn append(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.pairs.push((key.into(), value.into()));
+    }
+
+    /// The first value of the key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Every value of the key, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.pairs
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Every value of the key compared case insensitively, in order.
+    pub fn get_all_ci<'a>(&'a self, key: &'a StrCi) -> impl Iterator<Item = &'a str> {
+        self.pairs
+            .iter()
+            .filter(move |(k, _)| StrCi::new(k) == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The first value of the key compared case insensitively.
+    pub fn get_ci(&self, key: &StrCi) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| StrCi::new(k) == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Remove every pair of the key.
+    pub fn remove(&mut self, key: &str) {
+        self.pairs.retain(|(k, _)| k != key);
+    }
+
+    /// Replace the values of the key by a single value, at the place of the first one.
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+
+        match self.pairs.iter().position(|(k, _)| k == key) {
+            Some(idx) => {
+                self.pairs[idx].1 = value;
+
+                let mut i = 0;
+                self.pairs.retain(|(k, _)| {
+                    i += 1;
+                    i - 1 == idx || k != key
+                });
+            }
+            None => self.pairs.push((key.to_string(), value)),
+        }
+    }
+}
+
+/// Serializes the pairs, without leading `?`.
+impl Display for QueryString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (k, v)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+
+            f.write_str(&encode_component(k, EncodeSet::Form))?;
+            f.write_str("=")?;
+            f.write_str(&encode_component(v, EncodeSet::Form))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for QueryString {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self {
+            pairs: iter
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for QueryString {
+    type Err = UrlError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl<'a> IntoIterator for &'a QueryString {
+    type Item = (&'a str, &'a str);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (String, String)>,
+        fn(&'a (String, String)) -> (&'a str, &'a str),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// The chars accepted in a pair of a query: unreserved, sub-delims, `:`, `@`, `/`, `?` and
+/// `%`, the `%XX` sequences are validated by the decoding.
+fn is_query_char(c: char) -> bool {
+    super::parser::is_unreserved(c)
+        || super::parser::is_sub_delim(c)
+        || matches!(c, ':' | '@' | '/' | '?' | '%')
+}
+
+#[test]
+fn test_query_string() {
+    let q = QueryString::parse("a=1&B=2&a=3&flag&e=&x=a=b").unwrap();
+
+    assert_eq!(q.len(), 6);
+    assert_eq!(
+        q.iter().collect::<Vec<_>>(),
+        [
+            ("a", "1"),
+            ("B", "2"),
+            ("a", "3"),
+            ("flag", ""),
+            ("e", ""),
+            ("x", "a=b")
+        ]
+    );
+    assert_eq!(q.get("a"), Some("1"));
+    assert_eq!(q.get("b"), None);
+    assert_eq!(q.get_ci(StrCi::new("b")), Some("2"));
+    assert_eq!(q.get_all("a").collect::<Vec<_>>(), ["1", "3"]);
+    assert_eq!(q.get_all_ci(StrCi::new("A")).count(), 2);
+    assert_eq!(q.to_string(), "a=1&B=2&a=3&flag=&e=&x=a%3Db");
+    assert_eq!(
+        QueryString::parse(&q.to_string()).unwrap().get("x"),
+        Some("a=b")
+    );
+
+    let mut q = q;
+
+    q.set("a", "4");
+    assert_eq!(q.get_all("a").collect::<Vec<_>>(), ["4"]);
+    assert_eq!(q.iter().next(), Some(("a", "4")));
+    q.set("new", "é &+");
+    q.remove("flag");
+    q.append("B", "5");
+    assert_eq!(q.to_string(), "a=4&B=2&e=&x=a%3Db&new=%C3%A9+%26%2B&B=5");
+
+    let q = [("k", "v w")].into_iter().collect::<QueryString>();
+
+    assert_eq!(q.to_string(), "k=v+w");
+    assert_eq!("".parse::<QueryString>().unwrap(), QueryString::new());
+    assert_eq!("?".parse::<QueryString>().unwrap(), QueryString::new());
+
+    for (input, e) in [
+        ("a=1&&b=2", UrlError::EmptyQueryPair),
+        ("a=1&", UrlError::EmptyQueryPair),
+        ("=1", UrlError::EmptyQueryKey),
+        ("a=%zz", UrlError::InvalidPercentEncoding),
+        ("a=%FF", UrlError::InvalidUtf8),
+        ("a=b c", UrlError::InvalidChar(' ')),
+        ("a=b#c", UrlError::InvalidChar('#')),
+        ("a=é", UrlError::InvalidChar('é')),
+    ] {
+        assert_eq!(QueryString::parse(input), Err(e), "{input}");
+    }
+}
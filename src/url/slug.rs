@@ -0,0 +1,331 @@
+use super::{Result, UrlError};
+use crate::StrUtilsExt;
+
+/// Common english words removed by [SlugOptions::stop_words].
+pub const STOP_WORDS_EN: &[&str] = &[
+    "a", "an", "and", "at", "by", "for", "from", "in", "of", "on", "or", "the", "to", "with",
+];
+
+/// Common french words removed by [SlugOptions::stop_words].
+pub const STOP_WORDS_FR: &[&str] = &[
+    "a", "au", "aux", "d", "de", "des", "du", "en", "et", "l", "la", "le", "les", "un", "une",
+];
+
+/// The char between the words of a slug.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum SlugSeparator {
+    /// `-`, such as "cafe-2024".
+    #[default]
+    Hyphen,
+    /// `_`, such as "cafe_2024".
+    Underscore,
+}
+
+impl SlugSeparator {
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::Hyphen => '-',
+            Self::Underscore => '_',
+        }
+    }
+
+    /// The regular expression of a slug using this separator.
+    pub(crate) const fn pattern(self) -> &'static str {
+        match self {
+            Self::Hyphen => "^[a-z0-9]+(-[a-z0-9]+)*$",
+            Self::Underscore => "^[a-z0-9]+(_[a-z0-9]+)*$",
+        }
+    }
+}
+
+/// Options of [slugify].
+#[derive(Clone, Copy, Debug)]
+pub struct SlugOptions {
+    /// The maximum length in bytes, the slug is cut after the last complete word. No limit
+    /// by default.
+    pub max_len: Option<usize>,
+    /// The char between words, [SlugSeparator::Hyphen] by default.
+    pub separator: SlugSeparator,
+    /// The lowercase words removed from the slug, unless no word would remain. None by
+    /// default, see [STOP_WORDS_EN] and [STOP_WORDS_FR].
+    pub stop_words: &'static [&'static str],
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            max_len: None,
+            separator: SlugSeparator::Hyphen,
+            stop_words: &[],
+        }
+    }
+}
+
+/// Build a slug for an URL: the text is transliterated into lowercase ascii with
+/// [StrUtilsExt::no_accent_lowercase], and the words made of letters and digits are joined by
+/// the separator.
+///
+/// The slug is empty when the text has no letter nor digit, such as "!!!", it is then not
+/// valid for [validate_slug] and the caller should use a fallback, or give it to
+/// [unique_slug] which returns a number.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{slugify, SlugOptions, STOP_WORDS_FR};
+///
+/// let o = SlugOptions::default();
+///
+/// assert_eq!(slugify("Café à l'Été 2024!", o), "cafe-a-l-ete-2024");
+///
+/// let o = SlugOptions {
+///     max_len: Some(10),
+///     stop_words: STOP_WORDS_FR,
+///     ..o
+/// };
+///
+/// assert_eq!(slugify("Café à l'Été 2024!", o), "cafe-ete");
+/// ```
+pub fn slugify(s: &str, options: SlugOptions) -> String {
+    let lower = s.no_accent_lowercase();
+    let words = || {
+        lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+    };
+
+    let keep_all = words().all(|w| options.stop_words.contains(&w));
+    let max_len = options.max_len.unwrap_or(usize::MAX);
+    let separator = options.separator.as_char();
+    let mut out = String::new();
+
+    for word in words().filter(|w| keep_all || !options.stop_words.contains(w)) {
+        if out.is_empty() {
+            // a first word longer than the limit is cut.
+            out.push_str(word.truncate_bytes(max_len));
+            continue;
+        }
+
+        if out.len() + 1 + word.len() > max_len {
+            break;
+        }
+
+        out.push(separator);
+        out.push_str(word);
+    }
+
+    out
+}
+
+/// Returns the first slug among "slug", "slug-2", "slug-3", ... for which `exists` returns
+/// false. The words at the end are removed when the number does not fit in
+/// [SlugOptions::max_len], the first char of the slug is always kept even if the result is
+/// then longer than the limit. `exists` must eventually return false.
+///
+/// An empty slug, as returned by [slugify] for "!!!", is never returned: the first number
+/// among "1", "2", "3", ... for which `exists` returns false is returned instead.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{unique_slug, SlugOptions};
+///
+/// let taken = ["news", "news-2"];
+/// let slug = unique_slug("news", SlugOptions::default(), |s| taken.contains(&s));
+///
+/// assert_eq!(slug, "news-3");
+/// ```
+pub fn unique_slug<F>(slug: &str, options: SlugOptions, mut exists: F) -> String
+where
+    F: FnMut(&str) -> bool,
+{
+    if slug.is_empty() {
+        return (1..)
+            .map(|n: u64| n.to_string())
+            .find(|n| !exists(n))
+            .unwrap();
+    }
+
+    if !exists(slug) {
+        return slug.to_string();
+    }
+
+    let max_len = options.max_len.unwrap_or(usize::MAX);
+    let separator = options.separator.as_char();
+    let first_len = slug.chars().next().map_or(0, char::len_utf8);
+
+    for n in 2.. {
+        let suffix = format!("{separator}{n}");
+        let mut base = slug;
+
+        // remove whole words until the suffix fits, a slug made of the suffix alone such as
+        // "-2" would not be valid.
+        while base.len() > first_len && base.len() + suffix.len() > max_len {
+            base = match base.rfind(separator) {
+                Some(idx) if idx > 0 => &base[..idx],
+                _ => base.truncate_bytes(max_len.saturating_sub(suffix.len())),
+            };
+
+            if base.is_empty() {
+                base = &slug[..first_len];
+            }
+        }
+
+        let candidate = format!("{base}{suffix}");
+
+        if !exists(&candidate) {
+            return candidate;
+        }
+    }
+
+    unreachable!()
+}
+
+/// Validate a slug made of lowercase ascii letters and digits separated by single `-`, such
+/// as "cafe-a-l-ete-2024". See [validate_slug_with] for another separator.
+///
+/// # Example
+/// ```
+/// use str_utils::url::{validate_slug, UrlError};
+///
+/// assert!(validate_slug("cafe-2024").is_ok());
+/// assert_eq!(validate_slug("Cafe"), Err(UrlError::InvalidChar('C')));
+/// assert_eq!(validate_slug("cafe--2024"), Err(UrlError::InvalidSlug));
+/// ```
+pub fn validate_slug(s: &str) -> Result<()> {
+    validate_slug_with(s, SlugSeparator::Hyphen)
+}
+
+/// Validate a slug like [validate_slug], with the words separated by a [SlugSeparator] as
+/// built by [slugify].
+///
+/// # Example
+/// ```
+/// use str_utils::url::{validate_slug_with, SlugSeparator, UrlError};
+///
+/// assert!(validate_slug_with("cafe_2024", SlugSeparator::Underscore).is_ok());
+/// assert_eq!(
+///     validate_slug_with("cafe-2024", SlugSeparator::Underscore),
+///     Err(UrlError::InvalidChar('-'))
+/// );
+/// ```
+pub fn validate_slug_with(s: &str, separator: SlugSeparator) -> Result<()> {
+    let separator = separator.as_char();
+
+    if let Some(c) = s
+        .chars()
+        .find(|c| !c.is_ascii_lowercase() && !c.is_ascii_digit() && *c != separator)
+    {
+        return Err(UrlError::InvalidChar(c));
+    }
+
+    if s.split(separator).any(str::is_empty) {
+        return Err(UrlError::InvalidSlug);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_slugify() {
+    let o = SlugOptions::default();
+
+    for (input, expected) in [
+        ("Café à l'Été 2024!", "cafe-a-l-ete-2024"),
+        ("  Hello,   World  ", "hello-world"),
+        ("Straße", "strasse"),
+        ("--a--b--", "a-b"),
+        ("C++ & Rust", "c-rust"),
+        ("!!!", ""),
+        ("", ""),
+    ] {
+        assert_eq!(slugify(input, o), expected, "{input}");
+    }
+
+    let o = SlugOptions {
+        separator: SlugSeparator::Underscore,
+        ..o
+    };
+
+    assert_eq!(slugify("Hello World", o), "hello_world");
+    assert!(validate_slug_with(&slugify("Hello World", o), o.separator).is_ok());
+
+    let o = SlugOptions {
+        max_len: Some(11),
+        separator: SlugSeparator::Hyphen,
+        stop_words: STOP_WORDS_EN,
+    };
+
+    assert_eq!(slugify("The Lord of the Rings", o), "lord-rings");
+    assert_eq!(slugify("The Lord of the Ringsss", o), "lord");
+    assert_eq!(slugify("The Of", o), "the-of");
+    assert_eq!(slugify("Supercalifragilistic", o), "supercalifr");
+}
+
+#[test]
+fn test_unique_slug() {
+    let o = SlugOptions {
+        max_len: Some(10),
+        ..SlugOptions::default()
+    };
+
+    assert_eq!(unique_slug("a-b", o, |_| false), "a-b");
+    assert_eq!(unique_slug("a-b", o, |s| s == "a-b"), "a-b-2");
+    assert_eq!(
+        unique_slug("abc-def-gh", o, |s| s == "abc-def-gh"),
+        "abc-def-2"
+    );
+    assert_eq!(
+        unique_slug("abcdefghij", o, |s| s == "abcdefghij"),
+        "abcdefgh-2"
+    );
+
+    let taken = (2..=10).map(|n| format!("abc-def-{n}")).collect::<Vec<_>>();
+
+    assert_eq!(
+        unique_slug("abc-def-gh", o, |s| s == "abc-def-gh"
+            || taken.iter().any(|t| t == s)),
+        "abc-def-11"
+    );
+
+    // the first char is kept even when the slug becomes longer than the limit.
+    let o = SlugOptions {
+        max_len: Some(2),
+        ..SlugOptions::default()
+    };
+
+    assert_eq!(unique_slug("ab-c", o, |s| s == "ab-c"), "a-2");
+
+    let o = SlugOptions {
+        separator: SlugSeparator::Underscore,
+        ..o
+    };
+
+    assert_eq!(unique_slug("a_b", o, |s| s == "a_b"), "a_2");
+
+    // an empty slug is replaced by a number.
+    let o = SlugOptions::default();
+    let slug = slugify("!!!", o);
+
+    assert_eq!(slug, "");
+    assert_eq!(validate_slug(&slug), Err(UrlError::InvalidSlug));
+    assert_eq!(unique_slug(&slug, o, |_| false), "1");
+    assert_eq!(unique_slug(&slug, o, |s| s.is_empty() || s == "1"), "2");
+    assert!(validate_slug(&unique_slug(&slug, o, |s| s == "1")).is_ok());
+}
+
+#[test]
+fn test_validate_slug() {
+    for ok in ["a", "cafe-a-l-ete-2024", "2024"] {
+        assert!(validate_slug(ok).is_ok(), "{ok}");
+    }
+
+    assert_eq!(validate_slug(""), Err(UrlError::InvalidSlug));
+    assert_eq!(validate_slug("-a"), Err(UrlError::InvalidSlug));
+    assert_eq!(validate_slug("a-"), Err(UrlError::InvalidSlug));
+    assert_eq!(validate_slug("a_b"), Err(UrlError::InvalidChar('_')));
+    assert_eq!(validate_slug("é"), Err(UrlError::InvalidChar('é')));
+    assert!(validate_slug_with("a_b", SlugSeparator::Underscore).is_ok());
+    assert_eq!(
+        validate_slug_with("a__b", SlugSeparator::Underscore),
+        Err(UrlError::InvalidSlug)
+    );
+}